@@ -273,14 +273,20 @@ export default function AdminBankInitPage() {
         // Bank doesn't exist, proceed with initialization
       }
 
+      // every bank is priced with its own Pyth feed
+      const priceFeed = SUPPORTED_TOKENS.find(
+        (token) => token.mint === tokenMint
+      )!.priceFeed;
+
       const instruction = await program.methods
         .initBank(
           new BN(7000),
-          new PublicKey(tokenMint),
           new BN(8000),
           new BN(liquidationBonus),
           new BN(closeFactor),
-          new BN(interestRate)
+          new BN(interestRate),
+          new BN(3600), // config delay in seconds
+          Array.from(Buffer.from(priceFeed, "hex"))
         )
         .accounts({
           tokenMintAddress: new PublicKey(tokenMint),
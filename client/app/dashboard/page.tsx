@@ -116,15 +116,14 @@ export default function DashboardPage() {
       });
 
       const [userATA, bump] = PublicKey.findProgramAddressSync(
-        [Buffer.from("user"), publicKey!.toBuffer()],
+        [Buffer.from("user"), publicKey!.toBuffer(), Buffer.from([0])],
         program.programId
       );
 
       const instruction = await program.methods
-        .initUser()
-        .accounts({
-          mintAddress: token_address.usdc,
-          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        .initUser(0)
+        .accountsPartial({
+          userAccount: userATA,
           signer: publicKey,
         })
         .instruction();
@@ -18,7 +18,10 @@ import type { LendingApp } from "../../../../programs/lending-app/src/build/lend
 import IDL from "../../../../programs/lending-app/src/build/lending_app.json";
 import { PYTH_SOL_PRICE, PYTH_USDC_PRICE, token_address } from "@/lib/data";
 import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
-import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import {
+  getAssociatedTokenAddress,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
 
 export default function BorrowTab({
   bankInfo,
@@ -80,6 +83,9 @@ export default function BorrowTab({
       const program: Program<LendingApp> = new Program(IDL, { connection });
       const mintAddress =
         token == "USDC" ? token_address.usdc : token_address.sol;
+      // the position's other bank is priced for the health check
+      const otherMint =
+        token == "USDC" ? token_address.sol : token_address.usdc;
       // @ts-ignore
       const pyth = new PythSolanaReceiver({ connection, wallet: wallet });
 
@@ -87,6 +93,10 @@ export default function BorrowTab({
       const PriceFeedAccount = pyth
         .getPriceFeedAccountAddress(0, FEED_ID)
         .toBase58();
+      const OtherPriceFeedAccount = pyth.getPriceFeedAccountAddress(
+        0,
+        token == "USDC" ? PYTH_SOL_PRICE : PYTH_USDC_PRICE
+      );
       console.log(value);
 
       const [bankPda] = PublicKey.findProgramAddressSync(
@@ -95,7 +105,7 @@ export default function BorrowTab({
       );
 
       const [userPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("user"), wallet.publicKey!.toBuffer()],
+        [Buffer.from("user"), wallet.publicKey!.toBuffer(), Buffer.from([0])],
         program.programId
       );
 
@@ -112,10 +122,31 @@ export default function BorrowTab({
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           signer: wallet.publicKey!,
           priceUpdate: PriceFeedAccount,
+          recipientTokenAccount: await getAssociatedTokenAddress(
+            new PublicKey(mintAddress),
+            wallet.publicKey!,
+            false,
+            TOKEN_2022_PROGRAM_ID
+          ),
           bank: bankPda,
           userAccount: userPda,
           tokenBankAcc: treasuryPda,
         })
+        .remainingAccounts([
+          {
+            pubkey: PublicKey.findProgramAddressSync(
+              [Buffer.from("bank"), new PublicKey(otherMint).toBuffer()],
+              program.programId
+            )[0],
+            isSigner: false,
+            isWritable: false,
+          },
+          {
+            pubkey: OtherPriceFeedAccount,
+            isSigner: false,
+            isWritable: false,
+          },
+        ])
         .instruction();
       const getBlockHx = await connection.getLatestBlockhash("confirmed");
       const tx = new Transaction({
@@ -10,7 +10,6 @@ import { Connection, PublicKey, Transaction } from "@solana/web3.js";
 import {
   getAssociatedTokenAddress,
   TOKEN_2022_PROGRAM_ID,
-  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import type { LendingApp } from "../../../../programs/lending-app/src/build/lending_app";
 import { BN, Program } from "@coral-xyz/anchor";
@@ -102,17 +101,21 @@ export default function DepositTab({
         console.log(userTokenATA.toString(), "user ata");
         // Deriving the token bank account
         const [userAccount] = PublicKey.findProgramAddressSync(
-          [Buffer.from("user"), new PublicKey(wallet.publicKey).toBuffer()],
+          [
+            Buffer.from("user"),
+            new PublicKey(wallet.publicKey).toBuffer(),
+            Buffer.from([0]),
+          ],
           program.programId
         );
         console.log(transferAmount);
         const txInstruction = await program.methods
           .deposit(new BN(Number(transferAmount)))
-          .accountsStrict({
+          .accountsPartial({
             signer: wallet.publicKey,
+            beneficiary: wallet.publicKey,
             tokenMintAddress:
               token == "USDC" ? token_address.usdc : token_address.sol,
-            tokenProgram: TOKEN_PROGRAM_ID,
             tokenProgram2022: TOKEN_2022_PROGRAM_ID,
             bank: bankAccount,
             tokenBankAcc: bankTokenAccount,
@@ -72,12 +72,18 @@ export default function RepayTab({
     try {
       const decimalConversion = amt * 1000000000;
       const program: Program<LendingApp> = new Program(IDL, { connection });
+      const [userPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user"), wallet.publicKey.toBuffer(), Buffer.from([0])],
+        program.programId
+      );
       const ix = await program.methods
         .repay(new BN(decimalConversion))
-        .accounts({
+        .accountsPartial({
           repayMint: token == "USDC" ? token_address.usdc : token_address.sol,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           signer: wallet.publicKey,
+          borrower: wallet.publicKey,
+          userAccount: userPda,
         })
         .instruction();
 
@@ -25,7 +25,8 @@ import { Connection, PublicKey, Transaction } from "@solana/web3.js";
 import { LendingApp } from "../../../../programs/lending-app/src/build/lending_app";
 import { BN, Program } from "@coral-xyz/anchor";
 import IDL from "../../../../programs/lending-app/src/build/lending_app.json";
-import { token_address } from "@/lib/data";
+import { PYTH_SOL_PRICE, PYTH_USDC_PRICE, token_address } from "@/lib/data";
+import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
 import {
   getAssociatedTokenAddress,
   TOKEN_2022_PROGRAM_ID,
@@ -83,17 +84,49 @@ export default function WithdrawTab({
       // return;
     }
     const mint = token == "USDC" ? token_address.usdc : token_address.sol;
+    const otherMint = token == "USDC" ? token_address.sol : token_address.usdc;
 
     try {
       const program: Program<LendingApp> = new Program(IDL, { connection });
       const decimalAmount = Number(value) * 1000000000;
+      // @ts-ignore
+      const pyth = new PythSolanaReceiver({ connection, wallet: wallet });
+      const [priceUpdate, otherPriceUpdate] = (
+        token == "USDC"
+          ? [PYTH_USDC_PRICE, PYTH_SOL_PRICE]
+          : [PYTH_SOL_PRICE, PYTH_USDC_PRICE]
+      ).map((feed) => pyth.getPriceFeedAccountAddress(0, feed));
+      const [userPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user"), wallet.publicKey.toBuffer(), Buffer.from([0])],
+        program.programId
+      );
       const ix = await program.methods
         .withdraw(new BN(decimalAmount))
-        .accounts({
+        .accountsPartial({
           mint: mint,
+          priceUpdate,
+          userAccount: userPda,
+          recipientTokenAccount: await getAssociatedTokenAddress(
+            new PublicKey(mint),
+            wallet.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID
+          ),
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           signer: wallet.publicKey,
         })
+        // the position's other bank is priced for the health check
+        .remainingAccounts([
+          {
+            pubkey: PublicKey.findProgramAddressSync(
+              [Buffer.from("bank"), new PublicKey(otherMint).toBuffer()],
+              program.programId
+            )[0],
+            isSigner: false,
+            isWritable: false,
+          },
+          { pubkey: otherPriceUpdate, isSigner: false, isWritable: false },
+        ])
         .instruction();
       const bx = await connection.getLatestBlockhash();
       const tx = new Transaction({
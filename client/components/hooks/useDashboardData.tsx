@@ -21,6 +21,31 @@ export interface bankBalances {
   };
 }
 
+type UserAccount = Awaited<
+  ReturnType<Program<LendingApp>["account"]["user"]["fetch"]>
+>;
+
+// The dashboard shows the USDC and SOL banks, read from the position's per-bank slots.
+const tokenSlots = (account: UserAccount) => {
+  const slot = (mint: string) =>
+    account.positions.find((position) => position.mint.toBase58() === mint);
+  const usdc = slot(SUPPORTED_TOKENS[0].mint);
+  const sol = slot(SUPPORTED_TOKENS[1].mint);
+  const zero = new BN(0);
+  return {
+    depositedSol: sol?.deposited ?? zero,
+    depositedSolShares: sol?.depositedShares ?? zero,
+    borrowedSol: sol?.borrowed ?? zero,
+    borrowedSolShares: sol?.borrowedShares ?? zero,
+    depositedUsdc: usdc?.deposited ?? zero,
+    depositedUsdcShares: usdc?.depositedShares ?? zero,
+    borrowedUsdc: usdc?.borrowed ?? zero,
+    borrowedUsdcShares: usdc?.borrowedShares ?? zero,
+    mintAddress: new PublicKey(SUPPORTED_TOKENS[0].mint),
+    healthFactor: account.healthFactor,
+  };
+};
+
 export interface UserAccInfo {
   depositedSol: string | BN;
   depositedSolShares: string | BN;
@@ -122,7 +147,7 @@ export const useDashboardData = () => {
       });
 
       const [user] = PublicKey.findProgramAddressSync(
-        [Buffer.from("user"), publicKey.toBuffer()],
+        [Buffer.from("user"), publicKey.toBuffer(), Buffer.from([0])],
         program.programId
       );
       let accountInfo: {
@@ -138,7 +163,9 @@ export const useDashboardData = () => {
         healthFactor: BN;
       };
       try {
-        const getAccATA = await program.account.user.fetch(user, "confirmed");
+        const getAccATA = tokenSlots(
+          await program.account.user.fetch(user, "confirmed")
+        );
         setuserAccountInfo({
           borrowedSol: (
             getAccATA.borrowedSol.toNumber() / 1000000000
@@ -12,11 +12,13 @@ export const SUPPORTED_TOKENS = [
   {
     symbol: "USDC",
     mint: "9SFMpR2owdeZpGRLomHsDtx5rEf2bVuo3XCgSjyAVUf4",
+    priceFeed: PYTH_USDC_PRICE,
     img: "https://mcvzbtnedtysipzkwmuz.supabase.co/storage/v1/object/public/uploads/usdc-devnet.png",
   },
   {
     symbol: "SOL",
     mint: "J8NDF3RxtfZ5E2vks2NdchwE3PXNMNwUngCpEbMoLaoL",
+    priceFeed: PYTH_SOL_PRICE,
     img: "https://mcvzbtnedtysipzkwmuz.supabase.co/storage/v1/object/public/uploads/solana-coin.png",
   },
 ] as const;
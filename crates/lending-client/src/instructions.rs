@@ -0,0 +1,287 @@
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::instruction::Instruction,
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use lending_app::{accounts, instruction, User};
+
+use crate::{bank_address, event_authority_address, receipt_account, receipt_mint_address, treasure_address, UserPosition};
+
+// Every builder takes the token program of the bank's mint, the vault and receipt mint use the same one.
+
+/// Risk parameters of a new bank, in basis points except `interest_rate`.
+#[derive(Clone, Copy, Debug)]
+pub struct InitBankParams {
+    pub max_ltv: u64,
+    pub liquidation_threshold: u64,
+    pub liquidation_bonus: u64,
+    pub close_factor: u64,
+    pub interest_rate: u64,
+    // seconds, at least `MIN_CONFIG_DELAY`
+    pub config_delay: i64,
+    // Pyth feed the bank's positions are priced with
+    pub price_feed_id: [u8; 32],
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: lending_app::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn build_with_health(accounts: impl ToAccountMetas, data: impl InstructionData, health_accounts: Vec<AccountMeta>) -> Instruction {
+    let mut ix = build(accounts, data);
+    ix.accounts.extend(health_accounts);
+    ix
+}
+
+/// Remaining accounts for a health check: a (bank, price update) pair for every bank `user` has
+/// a position in besides `loaded`, in slot order. `price_update` maps a mint to its Pyth price account.
+pub fn health_accounts(user: &User, loaded: &[Pubkey], price_update: impl Fn(&Pubkey) -> Pubkey) -> Vec<AccountMeta> {
+    user.open_positions()
+        .filter(|position| !loaded.contains(&position.mint))
+        .flat_map(|position| {
+            [
+                AccountMeta::new_readonly(bank_address(&position.mint), false),
+                AccountMeta::new_readonly(price_update(&position.mint), false),
+            ]
+        })
+        .collect()
+}
+
+/// Creates the bank, its vault and its receipt mint. The signer becomes the bank authority.
+pub fn init_bank(signer: Pubkey, mint: Pubkey, token_program: Pubkey, params: InitBankParams) -> Instruction {
+    build(
+        accounts::InitializeBank {
+            signer,
+            bank: bank_address(&mint),
+            token_bank_acc: treasure_address(&mint),
+            receipt_mint: receipt_mint_address(&mint),
+            token_mint_address: mint,
+            system_program: system_program::ID,
+            token_program,
+            event_authority: event_authority_address(),
+            program: lending_app::ID,
+        },
+        instruction::InitBank {
+            max_ltv: params.max_ltv,
+            liquidation_threshold: params.liquidation_threshold,
+            liquidation_bonus: params.liquidation_bonus,
+            close_factor: params.close_factor,
+            interest_rate: params.interest_rate,
+            config_delay: params.config_delay,
+            price_feed_id: params.price_feed_id,
+        },
+    )
+}
+
+/// Opens position `index` for `signer`.
+pub fn init_user(signer: Pubkey, index: u8) -> Instruction {
+    build(
+        accounts::InitializeUser {
+            signer,
+            user_account: UserPosition::new(signer, index).address(),
+            system_program: system_program::ID,
+        },
+        instruction::InitUser { index },
+    )
+}
+
+/// Deposits from the signer's associated token account into `position`.
+pub fn deposit(signer: Pubkey, position: UserPosition, mint: Pubkey, token_program: Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::Deposit {
+            signer,
+            bank: bank_address(&mint),
+            token_bank_acc: treasure_address(&mint),
+            beneficiary: position.owner,
+            user_lending_program_acc: position.address(),
+            user_token_account: get_associated_token_address_with_program_id(&signer, &mint, &token_program),
+            token_mint_address: mint,
+            token_program_2022: token_program,
+            event_authority: event_authority_address(),
+            program: lending_app::ID,
+        },
+        instruction::Deposit { amount },
+    )
+}
+
+/// Withdraws `amount` (u64::MAX for everything) from `position` to any token account of the mint.
+/// A position with debt needs its `health_accounts` for every bank besides `mint`.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    signer: Pubkey,
+    position: UserPosition,
+    mint: Pubkey,
+    recipient_token_account: Pubkey,
+    price_update: Pubkey,
+    token_program: Pubkey,
+    amount: u64,
+    health_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build_with_health(
+        accounts::WithDraw {
+            signer,
+            recipient_token_account,
+            user_account: position.address(),
+            bank: bank_address(&mint),
+            bank_token_account: treasure_address(&mint),
+            mint,
+            price_update,
+            system_program: system_program::ID,
+            token_program,
+            event_authority: event_authority_address(),
+            program: lending_app::ID,
+        },
+        instruction::Withdraw { amount },
+        health_accounts,
+    )
+}
+
+/// Supplies `amount` from the signer's associated token account and mints the receipts to
+/// `receipt_account`, which can be any token account of the bank's receipt mint.
+pub fn supply(signer: Pubkey, mint: Pubkey, receipt_account: Pubkey, token_program: Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::Supply {
+            signer,
+            bank: bank_address(&mint),
+            bank_token_account: treasure_address(&mint),
+            signer_token_account: get_associated_token_address_with_program_id(&signer, &mint, &token_program),
+            receipt_mint: receipt_mint_address(&mint),
+            receipt_account,
+            mint,
+            token_program,
+            event_authority: event_authority_address(),
+            program: lending_app::ID,
+        },
+        instruction::Supply { amount },
+    )
+}
+
+/// Burns `shares` receipts from the signer's associated receipt account and pays them out
+/// to any token account of the mint.
+pub fn redeem_receipts(
+    signer: Pubkey,
+    mint: Pubkey,
+    recipient_token_account: Pubkey,
+    token_program: Pubkey,
+    shares: u64,
+) -> Instruction {
+    build(
+        accounts::RedeemReceipts {
+            signer,
+            bank: bank_address(&mint),
+            bank_token_account: treasure_address(&mint),
+            receipt_mint: receipt_mint_address(&mint),
+            signer_receipt_account: receipt_account(&signer, &mint, &token_program),
+            recipient_token_account,
+            mint,
+            token_program,
+            event_authority: event_authority_address(),
+            program: lending_app::ID,
+        },
+        instruction::RedeemReceipts { shares },
+    )
+}
+
+/// Borrows `amount` against `position`'s deposits to any token account of the mint.
+/// `health_accounts` price every bank of the position besides `mint`.
+#[allow(clippy::too_many_arguments)]
+pub fn borrow(
+    signer: Pubkey,
+    position: UserPosition,
+    mint: Pubkey,
+    recipient_token_account: Pubkey,
+    price_update: Pubkey,
+    token_program: Pubkey,
+    amount: u64,
+    health_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build_with_health(
+        accounts::Borrow {
+            signer,
+            recipient_token_account,
+            user_account: position.address(),
+            bank: bank_address(&mint),
+            token_bank_acc: treasure_address(&mint),
+            price_update,
+            mint,
+            token_program,
+            system_program: system_program::ID,
+            event_authority: event_authority_address(),
+            program: lending_app::ID,
+        },
+        instruction::Borrow { amount },
+        health_accounts,
+    )
+}
+
+/// Repays `amount` (u64::MAX for the whole debt) of `position` from the signer's associated token account.
+/// The signer does not have to own the position.
+pub fn repay(signer: Pubkey, position: UserPosition, mint: Pubkey, token_program: Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::Repay {
+            signer,
+            bank: bank_address(&mint),
+            bank_token_account: treasure_address(&mint),
+            repay_mint: mint,
+            user_token_account: get_associated_token_address_with_program_id(&signer, &mint, &token_program),
+            borrower: position.owner,
+            user_account: position.address(),
+            system_program: system_program::ID,
+            token_program,
+            event_authority: event_authority_address(),
+            program: lending_app::ID,
+        },
+        instruction::Repay { amount },
+    )
+}
+
+/// Liquidates `position`, paying from and receiving collateral into the liquidator's associated token accounts.
+/// `health_accounts` price every bank of the position besides the two mints.
+#[allow(clippy::too_many_arguments)]
+pub fn liquidate(
+    liquidator: Pubkey,
+    position: UserPosition,
+    collateral_mint: Pubkey,
+    borrowed_mint: Pubkey,
+    collateral_price_update: Pubkey,
+    borrowed_price_update: Pubkey,
+    token_program: Pubkey,
+    health_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build_with_health(
+        accounts::Liquidate {
+            signer: liquidator,
+            collateral_mint,
+            borrowed_mint,
+            collateral_bank: bank_address(&collateral_mint),
+            collateral_token_bank: treasure_address(&collateral_mint),
+            user_account: position.address(),
+            borrowed_bank: bank_address(&borrowed_mint),
+            borrowed_token_bank: treasure_address(&borrowed_mint),
+            liquidator_borrowed_token_account: get_associated_token_address_with_program_id(
+                &liquidator,
+                &borrowed_mint,
+                &token_program,
+            ),
+            liquidator_colleteral_token_account: get_associated_token_address_with_program_id(
+                &liquidator,
+                &collateral_mint,
+                &token_program,
+            ),
+            collateral_price_update,
+            borrowed_price_update,
+            token_program,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: event_authority_address(),
+            program: lending_app::ID,
+        },
+        instruction::Liquidate {},
+        health_accounts,
+    )
+}
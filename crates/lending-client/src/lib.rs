@@ -0,0 +1,12 @@
+//! Client for the lending program. Account lists are built from the program's own
+//! `#[derive(Accounts)]` structs, so a change to an instruction's accounts breaks the
+//! build here instead of producing transactions that fail on-chain.
+
+pub mod instructions;
+pub mod pda;
+pub mod state;
+
+pub use instructions::*;
+pub use lending_app::ID as PROGRAM_ID;
+pub use pda::*;
+pub use state::*;
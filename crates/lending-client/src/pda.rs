@@ -0,0 +1,98 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+pub fn bank_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bank", mint.as_ref()], &lending_app::ID).0
+}
+
+/// Token vault holding a bank's liquidity, it is its own authority.
+pub fn treasure_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"treasure", mint.as_ref()], &lending_app::ID).0
+}
+
+/// Mint of a bank's deposit receipts, one token per deposit share.
+pub fn receipt_mint_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"receipt", mint.as_ref()], &lending_app::ID).0
+}
+
+/// `owner`'s associated account for a bank's deposit receipts.
+pub fn receipt_account(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, &receipt_mint_address(mint), token_program)
+}
+
+pub fn user_address(owner: &Pubkey, index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[b"user", owner.as_ref(), &[index]], &lending_app::ID).0
+}
+
+/// Signer of the program's `emit_cpi!` events.
+pub fn event_authority_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &lending_app::ID).0
+}
+
+/// One of a wallet's positions, wallets can open several with different indexes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UserPosition {
+    pub owner: Pubkey,
+    pub index: u8,
+}
+
+impl UserPosition {
+    pub fn new(owner: Pubkey, index: u8) -> Self {
+        Self { owner, index }
+    }
+
+    pub fn address(&self) -> Pubkey {
+        user_address(&self.owner, self.index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Re-derives `address` from the seeds and its canonical bump, the way the program checks it.
+    fn assert_derived(address: Pubkey, seeds: &[&[u8]]) {
+        let (expected, bump) = Pubkey::find_program_address(seeds, &lending_app::ID);
+        assert_eq!(address, expected);
+        let bump = [bump];
+        let with_bump: Vec<&[u8]> = seeds.iter().copied().chain([&bump[..]]).collect();
+        assert_eq!(Pubkey::create_program_address(&with_bump, &lending_app::ID).unwrap(), address);
+    }
+
+    #[test]
+    fn bank_addresses_match_program_seeds() {
+        let mint = Pubkey::new_unique();
+        assert_derived(bank_address(&mint), &[b"bank", mint.as_ref()]);
+        assert_derived(treasure_address(&mint), &[b"treasure", mint.as_ref()]);
+        assert_derived(receipt_mint_address(&mint), &[b"receipt", mint.as_ref()]);
+        assert_derived(event_authority_address(), &[b"__event_authority"]);
+    }
+
+    #[test]
+    fn bank_addresses_are_distinct() {
+        let mint = Pubkey::new_unique();
+        let addresses = [bank_address(&mint), treasure_address(&mint), receipt_mint_address(&mint)];
+        assert_ne!(addresses[0], addresses[1]);
+        assert_ne!(addresses[0], addresses[2]);
+        assert_ne!(addresses[1], addresses[2]);
+        assert_ne!(bank_address(&mint), bank_address(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn user_address_depends_on_owner_and_index() {
+        let owner = Pubkey::new_unique();
+        assert_derived(user_address(&owner, 3), &[b"user", owner.as_ref(), &[3]]);
+        assert_ne!(user_address(&owner, 0), user_address(&owner, 1));
+        assert_ne!(user_address(&owner, 0), user_address(&Pubkey::new_unique(), 0));
+        assert_eq!(UserPosition::new(owner, 3).address(), user_address(&owner, 3));
+    }
+
+    #[test]
+    fn receipt_account_is_owner_ata_of_receipt_mint() {
+        let (owner, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(
+            receipt_account(&owner, &mint, &anchor_spl::token_2022::ID),
+            get_associated_token_address_with_program_id(&owner, &receipt_mint_address(&mint), &anchor_spl::token_2022::ID)
+        );
+    }
+}
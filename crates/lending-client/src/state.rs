@@ -0,0 +1,133 @@
+use anchor_lang::{AccountDeserialize, Result};
+use lending_app::{Bank, User};
+
+/// Decodes a bank account's data, discriminator included.
+pub fn decode_bank(data: &[u8]) -> Result<Bank> {
+    Bank::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a user account's data, discriminator included.
+pub fn decode_user(data: &[u8]) -> Result<User> {
+    User::try_deserialize(&mut &data[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{prelude::Pubkey, AccountSerialize};
+    use lending_app::{BankConfigUpdate, Position, MAX_POSITIONS};
+
+    use super::*;
+
+    fn sample_bank() -> Bank {
+        Bank {
+            authority: Pubkey::new_unique(),
+            mint_address: Pubkey::new_unique(),
+            total_deposits: 1_000_000,
+            total_deposit_shares: 990_000,
+            total_borrowed: 400_000,
+            total_borrowed_shares: 390_000,
+            liquidation_threshold: 8_000,
+            liquidation_bonus: 500,
+            close_factor: 5_000,
+            max_ltv: 7_500,
+            interest_rate: 500,
+            last_updated: 1_700_000_000,
+            guardian: Pubkey::new_unique(),
+            paused: true,
+            config_delay: 86_400,
+            pending_config: BankConfigUpdate { max_ltv: Some(7_000), ..BankConfigUpdate::default() },
+            pending_config_ready_at: 1_700_086_400,
+            deposit_cap: 10_000_000,
+            borrow_cap: 5_000_000,
+            outflow_limit: 2_000_000,
+            outflow_window: 3_600,
+            outflow_window_start: 1_699_999_000,
+            outflow_prev_window: 100,
+            outflow_cur_window: 200,
+            flash_loan_fee: 9,
+            flash_loan_amount: 0,
+            origination_fee: 10,
+            origination_fee_protocol_share: 2_000,
+            protocol_fees: 1_234,
+            swap_program: Pubkey::new_unique(),
+            min_first_deposit: 1_000,
+            decimals: 9,
+            price_feed_id: [7; 32],
+        }
+    }
+
+    fn sample_user() -> User {
+        let mut positions = [Position::default(); MAX_POSITIONS];
+        positions[0] = Position {
+            mint: Pubkey::new_unique(),
+            deposited: 5_000,
+            deposited_shares: 4_900,
+            ..Position::default()
+        };
+        positions[2] = Position {
+            mint: Pubkey::new_unique(),
+            borrowed: 2_000,
+            borrowed_shares: 1_950,
+            ..Position::default()
+        };
+        User {
+            positions,
+            health_factor: 0,
+            owner: Pubkey::new_unique(),
+            delegate: Pubkey::new_unique(),
+            delegate_can_receive: true,
+            index: 2,
+        }
+    }
+
+    fn serialize(account: &impl AccountSerialize) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn decode_bank_round_trips() {
+        let bank = sample_bank();
+        let decoded = decode_bank(&serialize(&bank)).unwrap();
+        assert_eq!(decoded.authority, bank.authority);
+        assert_eq!(decoded.mint_address, bank.mint_address);
+        assert_eq!(decoded.total_deposit_shares, bank.total_deposit_shares);
+        assert_eq!(decoded.total_borrowed_shares, bank.total_borrowed_shares);
+        assert_eq!(decoded.guardian, bank.guardian);
+        assert!(decoded.paused);
+        assert_eq!(decoded.pending_config.max_ltv, Some(7_000));
+        assert_eq!(decoded.pending_config.liquidation_bonus, None);
+        assert_eq!(decoded.swap_program, bank.swap_program);
+        assert_eq!(decoded.min_first_deposit, bank.min_first_deposit);
+        assert_eq!(decoded.decimals, 9);
+        assert_eq!(decoded.price_feed_id, bank.price_feed_id);
+    }
+
+    #[test]
+    fn decode_user_round_trips() {
+        let user = sample_user();
+        let decoded = decode_user(&serialize(&user)).unwrap();
+        let (deposit_mint, borrow_mint) = (user.positions[0].mint, user.positions[2].mint);
+        assert_eq!(decoded.deposit_shares(&deposit_mint), 4_900);
+        assert_eq!(decoded.borrow_shares(&borrow_mint), 1_950);
+        assert_eq!(decoded.open_positions().count(), 2);
+        assert_eq!(decoded.owner, user.owner);
+        assert_eq!(decoded.delegate, user.delegate);
+        assert!(decoded.delegate_can_receive);
+        assert_eq!(decoded.index, 2);
+    }
+
+    #[test]
+    fn decode_rejects_the_other_account_type() {
+        assert!(decode_user(&serialize(&sample_bank())).is_err());
+        assert!(decode_bank(&serialize(&sample_user())).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_truncated_data() {
+        let data = serialize(&sample_bank());
+        assert!(decode_bank(&data[..data.len() - 1]).is_err());
+        assert!(decode_bank(&data[..4]).is_err());
+    }
+}
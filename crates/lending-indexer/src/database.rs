@@ -0,0 +1,614 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use lending_app::{BankConfigUpdate, BankInitializedEvent, LeverageLoopEvent, LiquidateEvent};
+use anchor_lang::prelude::Pubkey;
+use serde::{Deserialize, Serialize};
+
+use crate::transaction::{LendingEvent, RpcTransaction};
+
+/// Bank and user state rebuilt from events, stored as a JSON file.
+/// Keys are base58 addresses: banks by mint, users by user account.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Database {
+    pub banks: BTreeMap<String, BankRecord>,
+    pub users: BTreeMap<String, UserRecord>,
+    // signatures already ingested, so a file can be replayed safely
+    pub processed: BTreeSet<String>,
+}
+
+// Fields default so database files written by older versions still load.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BankRecord {
+    pub authority: String,
+    pub guardian: String,
+    pub paused: bool,
+    pub closed: bool,
+    pub total_deposits: u64,
+    pub total_deposit_shares: u64,
+    pub total_borrowed: u64,
+    pub total_borrowed_shares: u64,
+    // deposit shares held as receipt tokens instead of in a position
+    pub receipt_supply: u64,
+    pub interest_accrued: u64,
+    pub flash_loan_volume: u64,
+    pub flash_loan_fees: u64,
+    pub protocol_fees_collected: u64,
+    pub pending_config: Option<ConfigValues>,
+    pub pending_config_ready_at: i64,
+    pub config_changes: Vec<ConfigChange>,
+    pub history: Vec<BankSnapshot>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BankSnapshot {
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub kind: String,
+    pub total_deposits: u64,
+    pub total_deposit_shares: u64,
+    pub total_borrowed: u64,
+    pub total_borrowed_shares: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub signer: String,
+    pub applied: Option<ConfigValues>,
+    pub cancelled: Option<ConfigValues>,
+}
+
+/// Serializable copy of `BankConfigUpdate`.
+#[derive(Serialize, Deserialize)]
+pub struct ConfigValues {
+    pub max_ltv: Option<u64>,
+    pub liquidation_threshold: Option<u64>,
+    pub liquidation_bonus: Option<u64>,
+    pub close_factor: Option<u64>,
+    pub interest_rate: Option<u64>,
+    pub config_delay: Option<i64>,
+    pub deposit_cap: Option<u64>,
+    pub borrow_cap: Option<u64>,
+    pub outflow_limit: Option<u64>,
+    pub outflow_window: Option<i64>,
+    pub flash_loan_fee: Option<u64>,
+    pub origination_fee: Option<u64>,
+    pub origination_fee_protocol_share: Option<u64>,
+    pub swap_program: Option<String>,
+    // hex
+    pub price_feed_id: Option<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserRecord {
+    pub owner: String,
+    pub delegate: Option<String>,
+    pub delegate_can_receive: bool,
+    pub closed: bool,
+    // by mint
+    pub positions: BTreeMap<String, PositionRecord>,
+    pub liquidations: Vec<LiquidationRecord>,
+    pub history: Vec<UserActivity>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PositionRecord {
+    pub deposited_shares: u64,
+    // tokens put in for the shares still held
+    pub deposit_cost_basis: u64,
+    // withdrawn above cost basis
+    pub interest_earned: u64,
+    pub borrowed_shares: u64,
+    // tokens borrowed (fees included) for the debt shares still open
+    pub borrow_principal: u64,
+    // repaid above principal
+    pub interest_paid: u64,
+    pub origination_fees_paid: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserActivity {
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub kind: String,
+    pub mint: String,
+    pub signer: String,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LiquidationRecord {
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub liquidator: String,
+    pub collateral_mint: String,
+    pub borrowed_mint: String,
+    pub repaid_amount: u64,
+    pub repaid_shares: u64,
+    pub seized_amount: u64,
+    pub seized_shares: u64,
+    pub liquidation_bonus: u64,
+}
+
+struct Source<'a> {
+    signature: &'a str,
+    slot: u64,
+}
+
+impl Database {
+    /// Applies every event of a successful transaction that was not ingested before.
+    /// Returns the number of events applied.
+    pub fn ingest(&mut self, transaction: &RpcTransaction) -> Result<usize, String> {
+        let signature = transaction.signature();
+        if !transaction.succeeded() || self.processed.contains(signature) {
+            return Ok(0);
+        }
+        let events = transaction.events()?;
+        let source = Source { signature, slot: transaction.slot };
+        for event in &events {
+            self.apply(&source, event);
+        }
+        self.processed.insert(signature.to_string());
+        Ok(events.len())
+    }
+
+    fn apply(&mut self, source: &Source, event: &LendingEvent) {
+        match event {
+            LendingEvent::Deposit(event) => {
+                let mint = event.mint.to_string();
+                self.bank_snapshot(source, &mint, "deposit", event.timestamp, |bank| {
+                    bank.total_deposits = event.total_deposits;
+                    bank.total_deposit_shares = event.total_deposit_shares;
+                });
+                let user = self.user(&event.user.to_string(), &event.owner.to_string());
+                let position = user.positions.entry(mint.clone()).or_default();
+                position.deposited_shares = event.user_deposited_shares;
+                position.deposit_cost_basis += event.amount;
+                user.history.push(activity(source, "deposit", &mint, event.signer, event.timestamp, event.amount, event.shares));
+            }
+            LendingEvent::Withdraw(event) => {
+                let mint = event.mint.to_string();
+                self.bank_snapshot(source, &mint, "withdraw", event.timestamp, |bank| {
+                    bank.total_deposits = event.total_deposits;
+                    bank.total_deposit_shares = event.total_deposit_shares;
+                });
+                let user = self.user(&event.user.to_string(), &event.owner.to_string());
+                let position = user.positions.entry(mint.clone()).or_default();
+                // the withdrawn shares take their share of the cost basis, anything above it is interest
+                let basis = proportion(position.deposit_cost_basis, event.shares, position.deposited_shares);
+                position.deposit_cost_basis -= basis;
+                position.interest_earned += event.amount.saturating_sub(basis);
+                position.deposited_shares = event.user_deposited_shares;
+                user.history.push(activity(source, "withdraw", &mint, event.signer, event.timestamp, event.amount, event.shares));
+            }
+            LendingEvent::Supply(event) => {
+                self.bank_snapshot(source, &event.mint.to_string(), "supply", event.timestamp, |bank| {
+                    bank.total_deposits = event.total_deposits;
+                    bank.total_deposit_shares = event.total_deposit_shares;
+                    bank.receipt_supply += event.shares;
+                });
+            }
+            LendingEvent::Redeem(event) => {
+                self.bank_snapshot(source, &event.mint.to_string(), "redeem", event.timestamp, |bank| {
+                    bank.total_deposits = event.total_deposits;
+                    bank.total_deposit_shares = event.total_deposit_shares;
+                    bank.receipt_supply = bank.receipt_supply.saturating_sub(event.shares);
+                });
+            }
+            LendingEvent::Borrow(event) => {
+                let mint = event.mint.to_string();
+                self.bank_snapshot(source, &mint, "borrow", event.timestamp, |bank| {
+                    bank.total_borrowed = event.total_borrowed;
+                    bank.total_borrowed_shares = event.total_borrowed_shares;
+                });
+                let user = self.user(&event.user.to_string(), &event.owner.to_string());
+                let position = user.positions.entry(mint.clone()).or_default();
+                position.borrowed_shares = event.user_borrowed_shares;
+                position.borrow_principal += event.amount + event.origination_fee;
+                position.origination_fees_paid += event.origination_fee;
+                user.history.push(activity(source, "borrow", &mint, event.signer, event.timestamp, event.amount, event.shares));
+            }
+            LendingEvent::Repay(event) => {
+                let mint = event.mint.to_string();
+                self.bank_snapshot(source, &mint, "repay", event.timestamp, |bank| {
+                    bank.total_borrowed = event.total_borrowed;
+                    bank.total_borrowed_shares = event.total_borrowed_shares;
+                });
+                let user = self.user(&event.user.to_string(), &event.owner.to_string());
+                let position = user.positions.entry(mint.clone()).or_default();
+                // the repaid shares take their share of the principal, anything above it is interest
+                let principal = proportion(position.borrow_principal, event.shares, position.borrowed_shares);
+                position.borrow_principal -= principal;
+                position.interest_paid += event.amount.saturating_sub(principal);
+                position.borrowed_shares = event.user_borrowed_shares;
+                user.history.push(activity(source, "repay", &mint, event.signer, event.timestamp, event.amount, event.shares));
+            }
+            LendingEvent::Liquidate(event) => self.apply_liquidation(source, event),
+            LendingEvent::LeverageLoop(event) => self.apply_leverage_loop(source, event),
+            LendingEvent::FlashLoan(event) => {
+                self.bank_snapshot(source, &event.mint.to_string(), "flash_loan", event.timestamp, |bank| {
+                    bank.total_deposits = event.total_deposits;
+                    bank.flash_loan_volume += event.amount;
+                    bank.flash_loan_fees += event.fee;
+                });
+            }
+            LendingEvent::InterestAccrued(event) => {
+                self.bank_snapshot(source, &event.mint.to_string(), "interest", event.timestamp, |bank| {
+                    bank.total_deposits = event.total_deposits;
+                    bank.total_borrowed = event.total_borrowed;
+                    bank.interest_accrued += event.interest;
+                });
+            }
+            LendingEvent::BankConfigChanged(event) => {
+                let bank = self.banks.entry(event.mint.to_string()).or_default();
+                bank.pending_config = (!event.pending.is_empty()).then(|| ConfigValues::from(&event.pending));
+                bank.pending_config_ready_at = event.pending_ready_at;
+                bank.config_changes.push(ConfigChange {
+                    signature: source.signature.to_string(),
+                    slot: source.slot,
+                    timestamp: event.timestamp,
+                    signer: event.signer.to_string(),
+                    applied: Some(ConfigValues::from(&event.applied)),
+                    cancelled: None,
+                });
+            }
+            LendingEvent::BankInitialized(event) => self.apply_bank_initialized(source, event),
+            LendingEvent::BankConfigCancelled(event) => {
+                let bank = self.banks.entry(event.mint.to_string()).or_default();
+                bank.pending_config = None;
+                bank.pending_config_ready_at = 0;
+                bank.config_changes.push(ConfigChange {
+                    signature: source.signature.to_string(),
+                    slot: source.slot,
+                    timestamp: event.timestamp,
+                    signer: event.signer.to_string(),
+                    applied: None,
+                    cancelled: Some(ConfigValues::from(&event.cancelled)),
+                });
+            }
+            LendingEvent::BankPaused(event) => {
+                self.banks.entry(event.mint.to_string()).or_default().paused = event.paused;
+            }
+            LendingEvent::GuardianSet(event) => {
+                self.banks.entry(event.mint.to_string()).or_default().guardian = event.guardian.to_string();
+            }
+            LendingEvent::ProtocolFeesCollected(event) => {
+                self.banks.entry(event.mint.to_string()).or_default().protocol_fees_collected += event.amount;
+            }
+            LendingEvent::BankClosed(event) => {
+                self.bank_snapshot(source, &event.mint.to_string(), "close", event.timestamp, |bank| {
+                    bank.closed = true;
+                    bank.total_deposits = 0;
+                    bank.total_deposit_shares = 0;
+                    bank.total_borrowed = 0;
+                    bank.total_borrowed_shares = 0;
+                });
+            }
+            LendingEvent::DelegateSet(event) => {
+                let user = self.user(&event.user.to_string(), &event.owner.to_string());
+                user.delegate = (event.delegate != Pubkey::default()).then(|| event.delegate.to_string());
+                user.delegate_can_receive = event.delegate_can_receive;
+            }
+            LendingEvent::UserClosed(event) => {
+                let user = self.user(&event.user.to_string(), &event.owner.to_string());
+                user.closed = true;
+                user.positions.clear();
+            }
+        }
+    }
+
+    // A bank can be re-created for a mint after it was closed, so the record starts over.
+    // The authority starts out as the guardian too.
+    fn apply_bank_initialized(&mut self, source: &Source, event: &BankInitializedEvent) {
+        let bank = self.banks.entry(event.mint.to_string()).or_default();
+        *bank = BankRecord {
+            authority: event.authority.to_string(),
+            guardian: event.authority.to_string(),
+            ..BankRecord::default()
+        };
+        bank.config_changes.push(ConfigChange {
+            signature: source.signature.to_string(),
+            slot: source.slot,
+            timestamp: event.timestamp,
+            signer: event.authority.to_string(),
+            applied: Some(ConfigValues::from(event)),
+            cancelled: None,
+        });
+    }
+
+    // A leverage loop is a run of borrows and deposits, summed up in one event.
+    fn apply_leverage_loop(&mut self, source: &Source, event: &LeverageLoopEvent) {
+        let debt_mint = event.debt_mint.to_string();
+        let collateral_mint = event.collateral_mint.to_string();
+        self.bank_snapshot(source, &debt_mint, "leverage", event.timestamp, |bank| {
+            bank.total_borrowed = event.total_borrowed;
+            bank.total_borrowed_shares = event.total_borrowed_shares;
+        });
+        self.bank_snapshot(source, &collateral_mint, "leverage", event.timestamp, |bank| {
+            bank.total_deposits = event.total_deposits;
+            bank.total_deposit_shares = event.total_deposit_shares;
+        });
+        let user = self.user(&event.user.to_string(), &event.owner.to_string());
+        let debt = user.positions.entry(debt_mint.clone()).or_default();
+        debt.borrowed_shares = event.user_borrowed_shares;
+        debt.borrow_principal += event.borrowed_amount + event.origination_fee;
+        debt.origination_fees_paid += event.origination_fee;
+        let collateral = user.positions.entry(collateral_mint.clone()).or_default();
+        collateral.deposited_shares = event.user_deposited_shares;
+        collateral.deposit_cost_basis += event.deposited_amount;
+        user.history.push(activity(
+            source,
+            "leverage_borrow",
+            &debt_mint,
+            event.signer,
+            event.timestamp,
+            event.borrowed_amount,
+            event.borrowed_shares,
+        ));
+        user.history.push(activity(
+            source,
+            "leverage_deposit",
+            &collateral_mint,
+            event.signer,
+            event.timestamp,
+            event.deposited_amount,
+            event.deposited_shares,
+        ));
+    }
+
+    // The repaid debt shares and seized collateral shares leave the position like a repay and a withdraw,
+    // with the seized tokens counted against the cost basis rather than as interest.
+    fn apply_liquidation(&mut self, source: &Source, event: &LiquidateEvent) {
+        let collateral_mint = event.collateral_mint.to_string();
+        let borrowed_mint = event.borrowed_mint.to_string();
+        self.bank_snapshot(source, &collateral_mint, "liquidate", event.timestamp, |bank| {
+            bank.total_deposits = event.collateral_total_deposits;
+            bank.total_deposit_shares = event.collateral_total_deposit_shares;
+        });
+        self.bank_snapshot(source, &borrowed_mint, "liquidate", event.timestamp, |bank| {
+            bank.total_borrowed = event.borrowed_total_borrowed;
+            bank.total_borrowed_shares = event.borrowed_total_borrowed_shares;
+        });
+        let user = self.user(&event.user.to_string(), &event.owner.to_string());
+        let debt = user.positions.entry(borrowed_mint.clone()).or_default();
+        let principal = proportion(debt.borrow_principal, event.repaid_shares, debt.borrowed_shares);
+        debt.borrow_principal -= principal;
+        debt.interest_paid += event.repaid_amount.saturating_sub(principal);
+        debt.borrowed_shares = event.user_borrowed_shares;
+        let collateral = user.positions.entry(collateral_mint.clone()).or_default();
+        let basis = proportion(collateral.deposit_cost_basis, event.seized_shares, collateral.deposited_shares);
+        collateral.deposit_cost_basis -= basis;
+        collateral.deposited_shares = event.user_deposited_shares;
+        user.liquidations.push(LiquidationRecord {
+            signature: source.signature.to_string(),
+            slot: source.slot,
+            timestamp: event.timestamp,
+            liquidator: event.liquidator.to_string(),
+            collateral_mint,
+            borrowed_mint,
+            repaid_amount: event.repaid_amount,
+            repaid_shares: event.repaid_shares,
+            seized_amount: event.seized_amount,
+            seized_shares: event.seized_shares,
+            liquidation_bonus: event.liquidation_bonus,
+        });
+    }
+
+    fn bank_snapshot(&mut self, source: &Source, mint: &str, kind: &str, timestamp: i64, update: impl FnOnce(&mut BankRecord)) {
+        let bank = self.banks.entry(mint.to_string()).or_default();
+        update(bank);
+        bank.history.push(BankSnapshot {
+            signature: source.signature.to_string(),
+            slot: source.slot,
+            timestamp,
+            kind: kind.to_string(),
+            total_deposits: bank.total_deposits,
+            total_deposit_shares: bank.total_deposit_shares,
+            total_borrowed: bank.total_borrowed,
+            total_borrowed_shares: bank.total_borrowed_shares,
+        });
+    }
+
+    fn user(&mut self, user: &str, owner: &str) -> &mut UserRecord {
+        let record = self.users.entry(user.to_string()).or_default();
+        record.owner = owner.to_string();
+        record
+    }
+}
+
+fn activity(source: &Source, kind: &str, mint: &str, signer: Pubkey, timestamp: i64, amount: u64, shares: u64) -> UserActivity {
+    UserActivity {
+        signature: source.signature.to_string(),
+        slot: source.slot,
+        timestamp,
+        kind: kind.to_string(),
+        mint: mint.to_string(),
+        signer: signer.to_string(),
+        amount,
+        shares,
+    }
+}
+
+// value * part / whole, capped at value
+fn proportion(value: u64, part: u64, whole: u64) -> u64 {
+    if whole == 0 || part >= whole {
+        return value;
+    }
+    (value as u128 * part as u128 / whole as u128) as u64
+}
+
+impl From<&BankConfigUpdate> for ConfigValues {
+    fn from(update: &BankConfigUpdate) -> Self {
+        Self {
+            max_ltv: update.max_ltv,
+            liquidation_threshold: update.liquidation_threshold,
+            liquidation_bonus: update.liquidation_bonus,
+            close_factor: update.close_factor,
+            interest_rate: update.interest_rate,
+            config_delay: update.config_delay,
+            deposit_cap: update.deposit_cap,
+            borrow_cap: update.borrow_cap,
+            outflow_limit: update.outflow_limit,
+            outflow_window: update.outflow_window,
+            flash_loan_fee: update.flash_loan_fee,
+            origination_fee: update.origination_fee,
+            origination_fee_protocol_share: update.origination_fee_protocol_share,
+            swap_program: update.swap_program.map(|program| program.to_string()),
+            price_feed_id: update.price_feed_id.map(|id| id.iter().map(|byte| format!("{byte:02x}")).collect()),
+        }
+    }
+}
+
+impl From<&BankInitializedEvent> for ConfigValues {
+    fn from(event: &BankInitializedEvent) -> Self {
+        Self {
+            max_ltv: Some(event.max_ltv),
+            liquidation_threshold: Some(event.liquidation_threshold),
+            liquidation_bonus: Some(event.liquidation_bonus),
+            close_factor: Some(event.close_factor),
+            interest_rate: Some(event.interest_rate),
+            config_delay: Some(event.config_delay),
+            ..Self::from(&BankConfigUpdate::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lending_app::{RedeemEvent, SupplyEvent};
+
+    use super::*;
+
+    // Fixture accounts are `Pubkey::new_from_array([n; 32])`.
+    fn key(n: u8) -> String {
+        Pubkey::new_from_array([n; 32]).to_string()
+    }
+
+    fn fixture() -> Vec<RpcTransaction> {
+        serde_json::from_str(include_str!("../tests/fixtures/transactions.json")).unwrap()
+    }
+
+    fn ingest_all(database: &mut Database, transactions: &[RpcTransaction]) -> usize {
+        transactions.iter().map(|transaction| database.ingest(transaction).unwrap()).sum()
+    }
+
+    #[test]
+    fn ingest_skips_duplicate_and_failed_transactions() {
+        let transactions = fixture();
+        let mut database = Database::default();
+        // the deposit transaction appears twice and the failed one is not applied
+        assert_eq!(ingest_all(&mut database, &transactions), 12);
+        assert_eq!(database.processed.len(), 6);
+        assert!(!database.processed.contains("failed"));
+        assert_eq!(database.users[&key(2)].positions[&key(10)].deposit_cost_basis, 729_625);
+
+        // replaying the same file changes nothing
+        assert_eq!(ingest_all(&mut database, &transactions), 0);
+        assert_eq!(database.users[&key(2)].history.len(), 2);
+    }
+
+    #[test]
+    fn ingest_rebuilds_banks() {
+        let mut database = Database::default();
+        ingest_all(&mut database, &fixture());
+
+        let collateral = &database.banks[&key(10)];
+        assert_eq!(collateral.authority, key(1));
+        assert_eq!(collateral.total_deposits, 730_075);
+        assert_eq!(collateral.total_deposit_shares, 729_625);
+        assert_eq!(collateral.flash_loan_volume, 500_000);
+        assert_eq!(collateral.flash_loan_fees, 450);
+        assert_eq!(collateral.config_changes.len(), 1);
+        assert_eq!(collateral.config_changes[0].applied.as_ref().unwrap().max_ltv, Some(7_500));
+
+        let debt = &database.banks[&key(11)];
+        assert_eq!(debt.total_borrowed, 257_500);
+        assert_eq!(debt.total_borrowed_shares, 252_500);
+        assert_eq!(debt.interest_accrued, 10_000);
+        assert_eq!(debt.protocol_fees_collected, 2_500);
+        assert!(debt.paused);
+        assert_eq!(debt.guardian, key(5));
+        assert!(!debt.closed);
+    }
+
+    #[test]
+    fn ingest_applies_liquidation_to_positions() {
+        let mut database = Database::default();
+        ingest_all(&mut database, &fixture());
+
+        let user = &database.users[&key(2)];
+        assert_eq!(user.owner, key(3));
+        assert_eq!(user.delegate, Some(key(6)));
+        assert!(user.delegate_can_receive);
+
+        let collateral = &user.positions[&key(10)];
+        assert_eq!(collateral.deposited_shares, 729_625);
+        assert_eq!(collateral.deposit_cost_basis, 729_625);
+        assert_eq!(collateral.interest_earned, 0);
+
+        // half the debt shares were repaid, the 5_000 above their principal is interest
+        let debt = &user.positions[&key(11)];
+        assert_eq!(debt.borrowed_shares, 252_500);
+        assert_eq!(debt.borrow_principal, 252_500);
+        assert_eq!(debt.interest_paid, 5_000);
+        assert_eq!(debt.origination_fees_paid, 5_000);
+
+        let liquidation = &user.liquidations[0];
+        assert_eq!(liquidation.liquidator, key(4));
+        assert_eq!(liquidation.repaid_shares, 252_500);
+        assert_eq!(liquidation.seized_shares, 270_375);
+    }
+
+    #[test]
+    fn supply_and_redeem_track_receipts() {
+        let mut database = Database::default();
+        let source = Source { signature: "receipts", slot: 1 };
+        let mint = Pubkey::new_from_array([10; 32]);
+        database.apply(&source, &LendingEvent::Supply(SupplyEvent {
+            signer: Pubkey::new_from_array([2; 32]),
+            mint,
+            receipt_account: Pubkey::new_from_array([7; 32]),
+            amount: 1_000,
+            shares: 1_000,
+            total_deposits: 1_000,
+            total_deposit_shares: 1_000,
+            timestamp: 1_700_000_000,
+        }));
+        database.apply(&source, &LendingEvent::Redeem(RedeemEvent {
+            signer: Pubkey::new_from_array([8; 32]),
+            mint,
+            recipient: Pubkey::new_from_array([9; 32]),
+            amount: 400,
+            shares: 400,
+            total_deposits: 600,
+            total_deposit_shares: 600,
+            timestamp: 1_700_000_010,
+        }));
+
+        // receipts don't open a position for anybody
+        assert!(database.users.is_empty());
+        let bank = &database.banks[&key(10)];
+        assert_eq!(bank.receipt_supply, 600);
+        assert_eq!(bank.total_deposits, 600);
+        assert_eq!(bank.history.len(), 2);
+    }
+
+    #[test]
+    fn proportion_scales_and_caps() {
+        assert_eq!(proportion(1_000, 250, 1_000), 250);
+        assert_eq!(proportion(1_000, 1, 3), 333);
+        // no shares left or more shares than held take the whole value
+        assert_eq!(proportion(1_000, 5, 0), 1_000);
+        assert_eq!(proportion(1_000, 2_000, 1_000), 1_000);
+        assert_eq!(proportion(u64::MAX, u64::MAX - 1, u64::MAX), u64::MAX - 1);
+    }
+}
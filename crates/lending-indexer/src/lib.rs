@@ -0,0 +1,10 @@
+//! Rebuilds lending-app bank and user state from the events the program emits through `emit_cpi!`.
+//!
+//! Input is a JSON array of `getTransaction` responses fetched with `"encoding": "json"`, ordered
+//! by slot. Events are read from the transactions' inner instructions.
+
+pub mod database;
+pub mod transaction;
+
+pub use database::*;
+pub use transaction::*;
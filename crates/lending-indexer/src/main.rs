@@ -0,0 +1,50 @@
+use std::{env, fs, process};
+
+use lending_indexer::{Database, RpcTransaction};
+
+// Usage: lending-indexer <transactions.json> [database.json]
+// The database file is created if it does not exist and updated in place.
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("usage: {} <transactions.json> [database.json]", args[0]);
+        process::exit(1);
+    }
+    let database_path = args.get(2).map(String::as_str).unwrap_or("lending-db.json");
+
+    if let Err(err) = run(&args[1], database_path) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run(transactions_path: &str, database_path: &str) -> Result<(), String> {
+    let transactions: Vec<RpcTransaction> = read_json(transactions_path)?;
+    let mut database: Database = match fs::metadata(database_path) {
+        Ok(_) => read_json(database_path)?,
+        Err(_) => Database::default(),
+    };
+
+    let mut events = 0;
+    for transaction in &transactions {
+        events += database.ingest(transaction)?;
+    }
+
+    let json = serde_json::to_string_pretty(&database).map_err(|err| err.to_string())?;
+    fs::write(database_path, json).map_err(|err| format!("{}: {}", database_path, err))?;
+
+    println!(
+        "{} transactions, {} events applied, {} banks and {} users in {}",
+        transactions.len(),
+        events,
+        database.banks.len(),
+        database.users.len(),
+        database_path
+    );
+    Ok(())
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, String> {
+    let data = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    serde_json::from_str(&data).map_err(|err| format!("{}: {}", path, err))
+}
@@ -0,0 +1,244 @@
+use anchor_lang::{event::EVENT_IX_TAG_LE, AnchorDeserialize, Discriminator};
+use lending_app::{
+    BankClosedEvent, BankConfigCancelledEvent, BankConfigChangedEvent, BankInitializedEvent, BankPausedEvent,
+    BorrowEvent, DelegateSetEvent, DepositEvent, FlashLoanEvent, GuardianSetEvent, InterestAccruedEvent,
+    LeverageLoopEvent, LiquidateEvent, ProtocolFeesCollectedEvent, RedeemEvent, RepayEvent, SupplyEvent,
+    UserClosedEvent, WithdrawEvent,
+};
+use serde::Deserialize;
+
+// Subset of a `getTransaction` RPC response with `"encoding": "json"`.
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub transaction: TransactionBody,
+    pub meta: Option<TransactionMeta>,
+}
+
+#[derive(Deserialize)]
+pub struct TransactionBody {
+    pub signatures: Vec<String>,
+    pub message: Message,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    pub account_keys: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionMeta {
+    pub err: Option<serde_json::Value>,
+    #[serde(default)]
+    pub inner_instructions: Vec<InnerInstructions>,
+    pub loaded_addresses: Option<LoadedAddresses>,
+}
+
+#[derive(Deserialize)]
+pub struct InnerInstructions {
+    pub index: u8,
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompiledInstruction {
+    pub program_id_index: usize,
+    pub data: String,
+}
+
+#[derive(Deserialize, Default)]
+pub struct LoadedAddresses {
+    #[serde(default)]
+    pub writable: Vec<String>,
+    #[serde(default)]
+    pub readonly: Vec<String>,
+}
+
+pub enum LendingEvent {
+    Deposit(DepositEvent),
+    Withdraw(WithdrawEvent),
+    Supply(SupplyEvent),
+    Redeem(RedeemEvent),
+    Borrow(BorrowEvent),
+    Repay(RepayEvent),
+    Liquidate(Box<LiquidateEvent>),
+    LeverageLoop(Box<LeverageLoopEvent>),
+    FlashLoan(FlashLoanEvent),
+    InterestAccrued(InterestAccruedEvent),
+    BankInitialized(BankInitializedEvent),
+    BankConfigChanged(Box<BankConfigChangedEvent>),
+    BankConfigCancelled(Box<BankConfigCancelledEvent>),
+    BankPaused(BankPausedEvent),
+    GuardianSet(GuardianSetEvent),
+    ProtocolFeesCollected(ProtocolFeesCollectedEvent),
+    BankClosed(BankClosedEvent),
+    DelegateSet(DelegateSetEvent),
+    UserClosed(UserClosedEvent),
+}
+
+impl RpcTransaction {
+    pub fn signature(&self) -> &str {
+        self.transaction.signatures.first().map(String::as_str).unwrap_or_default()
+    }
+
+    // Failed transactions still carry inner instructions up to the failure, but none of their state
+    // changes landed.
+    pub fn succeeded(&self) -> bool {
+        self.meta.as_ref().is_some_and(|meta| meta.err.is_none())
+    }
+
+    /// Lending events in the order they were emitted. Events are the self CPIs made by `emit_cpi!`:
+    /// an inner instruction to the lending program whose data starts with the event tag.
+    pub fn events(&self) -> Result<Vec<LendingEvent>, String> {
+        let Some(meta) = &self.meta else {
+            return Ok(Vec::new());
+        };
+
+        // Keys loaded from lookup tables come after the static keys, writable first.
+        let loaded = meta.loaded_addresses.as_ref();
+        let account_keys: Vec<&str> = self
+            .transaction
+            .message
+            .account_keys
+            .iter()
+            .chain(loaded.into_iter().flat_map(|loaded| loaded.writable.iter().chain(loaded.readonly.iter())))
+            .map(String::as_str)
+            .collect();
+        let program_id = lending_app::ID.to_string();
+
+        let mut inner_instructions: Vec<&InnerInstructions> = meta.inner_instructions.iter().collect();
+        inner_instructions.sort_by_key(|inner| inner.index);
+
+        let mut events = Vec::new();
+        for instruction in inner_instructions.iter().flat_map(|inner| inner.instructions.iter()) {
+            if account_keys.get(instruction.program_id_index) != Some(&program_id.as_str()) {
+                continue;
+            }
+            let data = bs58::decode(&instruction.data)
+                .into_vec()
+                .map_err(|err| format!("{}: bad instruction data: {}", self.signature(), err))?;
+            if let Some(event_data) = data.strip_prefix(EVENT_IX_TAG_LE) {
+                if let Some(event) = decode_event(event_data)
+                    .map_err(|err| format!("{}: bad event data: {}", self.signature(), err))?
+                {
+                    events.push(event);
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Decodes discriminator + borsh event data. Unknown discriminators are skipped
+/// so older indexers keep working when the program adds events.
+pub fn decode_event(data: &[u8]) -> std::io::Result<Option<LendingEvent>> {
+    if data.len() < 8 {
+        return Ok(None);
+    }
+    let (discriminator, mut body) = data.split_at(8);
+    let event = match discriminator {
+        d if d == DepositEvent::DISCRIMINATOR => LendingEvent::Deposit(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == WithdrawEvent::DISCRIMINATOR => LendingEvent::Withdraw(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == SupplyEvent::DISCRIMINATOR => LendingEvent::Supply(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == RedeemEvent::DISCRIMINATOR => LendingEvent::Redeem(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == BorrowEvent::DISCRIMINATOR => LendingEvent::Borrow(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == RepayEvent::DISCRIMINATOR => LendingEvent::Repay(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == LiquidateEvent::DISCRIMINATOR => {
+            LendingEvent::Liquidate(Box::new(AnchorDeserialize::deserialize(&mut body)?))
+        }
+        d if d == LeverageLoopEvent::DISCRIMINATOR => {
+            LendingEvent::LeverageLoop(Box::new(AnchorDeserialize::deserialize(&mut body)?))
+        }
+        d if d == FlashLoanEvent::DISCRIMINATOR => LendingEvent::FlashLoan(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == InterestAccruedEvent::DISCRIMINATOR => {
+            LendingEvent::InterestAccrued(AnchorDeserialize::deserialize(&mut body)?)
+        }
+        d if d == BankInitializedEvent::DISCRIMINATOR => {
+            LendingEvent::BankInitialized(AnchorDeserialize::deserialize(&mut body)?)
+        }
+        d if d == BankConfigChangedEvent::DISCRIMINATOR => {
+            LendingEvent::BankConfigChanged(Box::new(AnchorDeserialize::deserialize(&mut body)?))
+        }
+        d if d == BankConfigCancelledEvent::DISCRIMINATOR => {
+            LendingEvent::BankConfigCancelled(Box::new(AnchorDeserialize::deserialize(&mut body)?))
+        }
+        d if d == BankPausedEvent::DISCRIMINATOR => LendingEvent::BankPaused(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == GuardianSetEvent::DISCRIMINATOR => LendingEvent::GuardianSet(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == ProtocolFeesCollectedEvent::DISCRIMINATOR => {
+            LendingEvent::ProtocolFeesCollected(AnchorDeserialize::deserialize(&mut body)?)
+        }
+        d if d == BankClosedEvent::DISCRIMINATOR => LendingEvent::BankClosed(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == DelegateSetEvent::DISCRIMINATOR => LendingEvent::DelegateSet(AnchorDeserialize::deserialize(&mut body)?),
+        d if d == UserClosedEvent::DISCRIMINATOR => LendingEvent::UserClosed(AnchorDeserialize::deserialize(&mut body)?),
+        _ => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{prelude::Pubkey, Event};
+
+    use super::*;
+
+    fn fixture(signature: &str) -> RpcTransaction {
+        let transactions: Vec<RpcTransaction> =
+            serde_json::from_str(include_str!("../tests/fixtures/transactions.json")).unwrap();
+        transactions.into_iter().find(|transaction| transaction.signature() == signature).unwrap()
+    }
+
+    #[test]
+    fn decode_event_round_trips() {
+        let event = FlashLoanEvent {
+            mint: Pubkey::new_unique(),
+            signer: Pubkey::new_unique(),
+            amount: 500_000,
+            fee: 450,
+            total_deposits: 730_075,
+            timestamp: 1_700_000_040,
+        };
+        let Some(LendingEvent::FlashLoan(decoded)) = decode_event(&event.data()).unwrap() else {
+            panic!("expected a flash loan event");
+        };
+        assert_eq!(decoded.mint, event.mint);
+        assert_eq!(decoded.signer, event.signer);
+        assert_eq!(decoded.amount, event.amount);
+        assert_eq!(decoded.fee, event.fee);
+        assert_eq!(decoded.total_deposits, event.total_deposits);
+        assert_eq!(decoded.timestamp, event.timestamp);
+    }
+
+    #[test]
+    fn decode_event_skips_unknown_and_short_data() {
+        assert!(decode_event(&[0xff; 16]).unwrap().is_none());
+        assert!(decode_event(&[1, 2, 3]).unwrap().is_none());
+    }
+
+    #[test]
+    fn decode_event_rejects_truncated_body() {
+        let event = BankPausedEvent { mint: Pubkey::new_unique(), signer: Pubkey::new_unique(), paused: true, timestamp: 1 };
+        let data = event.data();
+        assert!(decode_event(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn events_ignore_other_programs() {
+        // the borrow transaction also carries a withdraw event emitted by another program
+        let events = fixture("borrow").events().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], LendingEvent::Borrow(_)));
+    }
+
+    #[test]
+    fn events_skip_unknown_discriminators() {
+        let events = fixture("flash").events().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], LendingEvent::FlashLoan(_)));
+    }
+}
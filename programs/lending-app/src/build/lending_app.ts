@@ -14,101 +14,107 @@ export type LendingApp = {
   },
   "instructions": [
     {
-      "name": "borrow",
+      "name": "applyBankConfig",
       "discriminator": [
-        228,
-        253,
-        131,
-        202,
-        207,
-        116,
-        89,
-        18
+        226,
+        46,
+        220,
+        115,
+        182,
+        169,
+        236,
+        179
       ],
       "accounts": [
         {
           "name": "signer",
-          "writable": true,
           "signer": true
         },
         {
-          "name": "userWantedTokenAccount",
+          "name": "bank",
           "writable": true,
           "pda": {
             "seeds": [
               {
-                "kind": "account",
-                "path": "signer"
-              },
-              {
-                "kind": "account",
-                "path": "tokenProgram"
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
               },
               {
                 "kind": "account",
                 "path": "mint"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "userAccount",
-          "writable": true,
+          "name": "mint"
+        },
+        {
+          "name": "eventAuthority",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
+                  95,
+                  95,
                   101,
-                  114
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
                 ]
-              },
-              {
-                "kind": "account",
-                "path": "signer"
               }
             ]
           }
         },
+        {
+          "name": "program"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "borrow",
+      "discriminator": [
+        228,
+        253,
+        131,
+        202,
+        207,
+        116,
+        89,
+        18
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "recipientTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
         {
           "name": "bank",
           "writable": true,
@@ -164,13 +170,41 @@ export type LendingApp = {
         {
           "name": "tokenProgram"
         },
-        {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
-        },
         {
           "name": "systemProgram",
           "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
         }
       ],
       "args": [
@@ -181,16 +215,92 @@ export type LendingApp = {
       ]
     },
     {
-      "name": "deposit",
+      "name": "cancelBankConfig",
       "discriminator": [
-        242,
-        35,
-        198,
-        137,
-        82,
-        225,
-        242,
-        182
+        238,
+        204,
+        125,
+        30,
+        213,
+        168,
+        71,
+        140
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "closeBank",
+      "discriminator": [
+        5,
+        98,
+        148,
+        223,
+        249,
+        112,
+        102,
+        111
       ],
       "accounts": [
         {
@@ -214,13 +324,13 @@ export type LendingApp = {
               },
               {
                 "kind": "account",
-                "path": "tokenMintAddress"
+                "path": "mint"
               }
             ]
           }
         },
         {
-          "name": "tokenBankAcc",
+          "name": "bankTokenAccount",
           "writable": true,
           "pda": {
             "seeds": [
@@ -239,122 +349,152 @@ export type LendingApp = {
               },
               {
                 "kind": "account",
-                "path": "tokenMintAddress"
+                "path": "mint"
               }
             ]
           }
         },
         {
-          "name": "userLendingProgramAcc",
+          "name": "receiptMint",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
+                  114,
+                  101,
+                  99,
                   101,
-                  114
+                  105,
+                  112,
+                  116
                 ]
               },
               {
                 "kind": "account",
-                "path": "signer"
+                "path": "mint"
               }
             ]
           }
         },
         {
-          "name": "userTokenAccount",
-          "writable": true,
+          "name": "authorityTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
           "pda": {
             "seeds": [
               {
-                "kind": "account",
-                "path": "signer"
-              },
-              {
-                "kind": "account",
-                "path": "tokenProgram2022"
-              },
-              {
-                "kind": "account",
-                "path": "tokenMintAddress"
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "tokenMintAddress"
+          "name": "program"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "closeUser",
+      "discriminator": [
+        86,
+        219,
+        138,
+        140,
+        236,
+        24,
+        118,
+        200
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
         },
         {
-          "name": "tokenProgram"
+          "name": "userAccount",
+          "writable": true
         },
         {
-          "name": "tokenProgram2022"
-        }
-      ],
-      "args": [
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
         {
-          "name": "amount",
-          "type": "u64"
+          "name": "program"
         }
-      ]
+      ],
+      "args": []
     },
     {
-      "name": "initBank",
+      "name": "collectProtocolFees",
       "discriminator": [
-        73,
-        111,
-        27,
-        243,
-        202,
-        129,
-        159,
-        80
+        22,
+        67,
+        23,
+        98,
+        150,
+        178,
+        70,
+        220
       ],
       "accounts": [
         {
           "name": "signer",
-          "writable": true,
           "signer": true
         },
         {
@@ -373,13 +513,13 @@ export type LendingApp = {
               },
               {
                 "kind": "account",
-                "path": "tokenMintAddress"
+                "path": "mint"
               }
             ]
           }
         },
         {
-          "name": "tokenBankAcc",
+          "name": "bankTokenAccount",
           "writable": true,
           "pda": {
             "seeds": [
@@ -398,112 +538,67 @@ export type LendingApp = {
               },
               {
                 "kind": "account",
-                "path": "tokenMintAddress"
+                "path": "mint"
               }
             ]
           }
         },
         {
-          "name": "tokenMintAddress"
+          "name": "feeReceiver",
+          "writable": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "mint"
         },
         {
           "name": "tokenProgram"
-        }
-      ],
-      "args": [
-        {
-          "name": "maxLtv",
-          "type": "u64"
-        },
-        {
-          "name": "mintAddress",
-          "type": "pubkey"
-        },
-        {
-          "name": "liquidationThreshold",
-          "type": "u64"
-        },
-        {
-          "name": "liquidationBonus",
-          "type": "u64"
-        },
-        {
-          "name": "closeFactor",
-          "type": "u64"
-        },
-        {
-          "name": "interestRate",
-          "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "initUser",
-      "discriminator": [
-        14,
-        51,
-        68,
-        159,
-        237,
-        78,
-        158,
-        102
-      ],
-      "accounts": [
-        {
-          "name": "signer",
-          "writable": true,
-          "signer": true
         },
         {
-          "name": "userAccount",
-          "writable": true,
+          "name": "eventAuthority",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
+                  95,
+                  95,
                   101,
-                  114
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
                 ]
-              },
-              {
-                "kind": "account",
-                "path": "signer"
               }
             ]
           }
         },
         {
-          "name": "mintAddress"
-        },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "tokenProgram"
+          "name": "program"
         }
       ],
       "args": []
     },
     {
-      "name": "liquidate",
+      "name": "deposit",
       "discriminator": [
-        223,
-        179,
-        226,
-        125,
-        48,
-        46,
-        39,
-        74
+        242,
+        35,
+        198,
+        137,
+        82,
+        225,
+        242,
+        182
       ],
       "accounts": [
         {
@@ -512,13 +607,7 @@ export type LendingApp = {
           "signer": true
         },
         {
-          "name": "collateralMint"
-        },
-        {
-          "name": "borrowedMint"
-        },
-        {
-          "name": "collateralBank",
+          "name": "bank",
           "writable": true,
           "pda": {
             "seeds": [
@@ -533,13 +622,13 @@ export type LendingApp = {
               },
               {
                 "kind": "account",
-                "path": "collateralMint"
+                "path": "tokenMintAddress"
               }
             ]
           }
         },
         {
-          "name": "collateralTokenBank",
+          "name": "tokenBankAcc",
           "writable": true,
           "pda": {
             "seeds": [
@@ -558,115 +647,34 @@ export type LendingApp = {
               },
               {
                 "kind": "account",
-                "path": "collateralMint"
+                "path": "tokenMintAddress"
               }
             ]
           }
         },
         {
-          "name": "userCollateralTokenAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  117,
-                  115,
-                  101,
-                  114
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "signer"
-              }
-            ]
-          }
+          "name": "beneficiary"
         },
         {
-          "name": "borrowedBank",
+          "name": "userLendingProgramAcc",
+          "writable": true
+        },
+        {
+          "name": "userTokenAccount",
           "writable": true,
           "pda": {
             "seeds": [
               {
-                "kind": "const",
-                "value": [
-                  98,
-                  97,
-                  110,
-                  107
-                ]
+                "kind": "account",
+                "path": "signer"
               },
               {
                 "kind": "account",
-                "path": "borrowedMint"
-              }
-            ]
-          }
-        },
-        {
-          "name": "borrowedTokenBank",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  116,
-                  114,
-                  101,
-                  97,
-                  115,
-                  117,
-                  114,
-                  101
-                ]
+                "path": "tokenProgram2022"
               },
               {
                 "kind": "account",
-                "path": "borrowedMint"
-              }
-            ]
-          }
-        },
-        {
-          "name": "userBorrowedTokenAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  117,
-                  115,
-                  101,
-                  114
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "signer"
-              }
-            ]
-          }
-        },
-        {
-          "name": "liquidatorBorrowedTokenAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "account",
-                "path": "signer"
-              },
-              {
-                "kind": "account",
-                "path": "tokenProgram"
-              },
-              {
-                "kind": "account",
-                "path": "borrowedMint"
+                "path": "tokenMintAddress"
               }
             ],
             "program": {
@@ -709,90 +717,62 @@ export type LendingApp = {
           }
         },
         {
-          "name": "liquidatorColleteralTokenAccount",
-          "writable": true,
+          "name": "tokenMintAddress"
+        },
+        {
+          "name": "tokenProgram2022"
+        },
+        {
+          "name": "eventAuthority",
           "pda": {
             "seeds": [
               {
-                "kind": "account",
-                "path": "signer"
-              },
-              {
-                "kind": "account",
-                "path": "tokenProgram"
-              },
-              {
-                "kind": "account",
-                "path": "collateralMint"
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "priceUpdate"
-        },
-        {
-          "name": "tokenProgram"
-        },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+          "name": "program"
         }
       ],
-      "args": []
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
     },
     {
-      "name": "repay",
+      "name": "depositExactShares",
       "discriminator": [
-        234,
-        103,
-        67,
-        82,
-        208,
-        234,
-        219,
-        166
+        2,
+        253,
+        143,
+        93,
+        140,
+        30,
+        22,
+        106
       ],
       "accounts": [
         {
@@ -816,13 +796,13 @@ export type LendingApp = {
               },
               {
                 "kind": "account",
-                "path": "repayMint"
+                "path": "tokenMintAddress"
               }
             ]
           }
         },
         {
-          "name": "bankTokenAccount",
+          "name": "tokenBankAcc",
           "writable": true,
           "pda": {
             "seeds": [
@@ -841,13 +821,17 @@ export type LendingApp = {
               },
               {
                 "kind": "account",
-                "path": "repayMint"
+                "path": "tokenMintAddress"
               }
             ]
           }
         },
         {
-          "name": "repayMint"
+          "name": "beneficiary"
+        },
+        {
+          "name": "userLendingProgramAcc",
+          "writable": true
         },
         {
           "name": "userTokenAccount",
@@ -860,11 +844,11 @@ export type LendingApp = {
               },
               {
                 "kind": "account",
-                "path": "tokenProgram"
+                "path": "tokenProgram2022"
               },
               {
                 "kind": "account",
-                "path": "repayMint"
+                "path": "tokenMintAddress"
               }
             ],
             "program": {
@@ -907,137 +891,157 @@ export type LendingApp = {
           }
         },
         {
-          "name": "userAccount",
-          "writable": true,
+          "name": "tokenMintAddress"
+        },
+        {
+          "name": "tokenProgram2022"
+        },
+        {
+          "name": "eventAuthority",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
+                  95,
+                  95,
                   101,
-                  114
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
                 ]
-              },
-              {
-                "kind": "account",
-                "path": "signer"
               }
             ]
           }
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "tokenProgram"
+          "name": "program"
         }
       ],
       "args": [
         {
-          "name": "amount",
+          "name": "shares",
+          "type": "u64"
+        },
+        {
+          "name": "maxAmount",
           "type": "u64"
         }
       ]
     },
     {
-      "name": "withdraw",
+      "name": "flashBorrow",
       "discriminator": [
-        183,
-        18,
-        70,
-        156,
-        148,
-        109,
-        161,
-        34
+        166,
+        221,
+        220,
+        25,
+        61,
+        73,
+        127,
+        240
       ],
       "accounts": [
         {
           "name": "signer",
-          "writable": true,
           "signer": true
         },
         {
-          "name": "userTokenAccount",
+          "name": "bank",
           "writable": true,
           "pda": {
             "seeds": [
               {
-                "kind": "account",
-                "path": "signer"
-              },
-              {
-                "kind": "account",
-                "path": "tokenProgram"
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
               },
               {
                 "kind": "account",
                 "path": "mint"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "userAccount",
+          "name": "bankTokenAccount",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
+                  116,
+                  114,
                   101,
-                  114
-                ]
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
               },
               {
                 "kind": "account",
-                "path": "signer"
+                "path": "mint"
               }
             ]
           }
         },
+        {
+          "name": "recipientTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "instructions",
+          "address": "Sysvar1nstructions1111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "flashRepay",
+      "discriminator": [
+        182,
+        143,
+        19,
+        23,
+        39,
+        221,
+        184,
+        78
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "signer": true
+        },
         {
           "name": "bank",
           "writable": true,
@@ -1084,59 +1088,2446 @@ export type LendingApp = {
             ]
           }
         },
+        {
+          "name": "userTokenAccount",
+          "writable": true
+        },
         {
           "name": "mint"
         },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "initBank",
+      "discriminator": [
+        73,
+        111,
+        27,
+        243,
+        202,
+        129,
+        159,
+        80
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "tokenMintAddress"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenBankAcc",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "tokenMintAddress"
+              }
+            ]
+          }
+        },
+        {
+          "name": "receiptMint",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "tokenMintAddress"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenMintAddress"
+        },
         {
           "name": "systemProgram",
           "address": "11111111111111111111111111111111"
         },
         {
-          "name": "tokenProgram"
+          "name": "tokenProgram",
+          "address": "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
         }
       ],
       "args": [
         {
-          "name": "amount",
+          "name": "maxLtv",
+          "type": "u64"
+        },
+        {
+          "name": "liquidationThreshold",
+          "type": "u64"
+        },
+        {
+          "name": "liquidationBonus",
+          "type": "u64"
+        },
+        {
+          "name": "closeFactor",
+          "type": "u64"
+        },
+        {
+          "name": "interestRate",
           "type": "u64"
+        },
+        {
+          "name": "configDelay",
+          "type": "i64"
+        },
+        {
+          "name": "priceFeedId",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
         }
       ]
-    }
-  ],
-  "accounts": [
-    {
-      "name": "bank",
-      "discriminator": [
-        142,
-        49,
-        166,
-        242,
-        50,
-        66,
-        97,
-        188
-      ]
     },
     {
-      "name": "priceUpdateV2",
+      "name": "initUser",
       "discriminator": [
-        34,
-        241,
-        35,
-        99,
-        157,
-        126,
-        244,
-        205
+        14,
+        51,
+        68,
+        159,
+        237,
+        78,
+        158,
+        102
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "index",
+          "type": "u8"
+        }
       ]
     },
     {
-      "name": "user",
+      "name": "liquidate",
       "discriminator": [
-        159,
-        117,
-        95,
-        227,
+        223,
+        179,
+        226,
+        125,
+        48,
+        46,
+        39,
+        74
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "collateralMint"
+        },
+        {
+          "name": "borrowedMint"
+        },
+        {
+          "name": "collateralBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "collateralMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "collateralTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "collateralMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "borrowedBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "borrowedMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "borrowedTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "borrowedMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "liquidatorBorrowedTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "signer"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "borrowedMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "liquidatorColleteralTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "signer"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "collateralMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "collateralPriceUpdate"
+        },
+        {
+          "name": "borrowedPriceUpdate"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "loopLeverage",
+      "discriminator": [
+        189,
+        0,
+        175,
+        145,
+        236,
+        83,
+        14,
+        118
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "debtMint"
+        },
+        {
+          "name": "collateralMint"
+        },
+        {
+          "name": "debtBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "debtMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "debtTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "debtMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "collateralBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "collateralMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "collateralTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "collateralMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "signerDebtAccount",
+          "writable": true
+        },
+        {
+          "name": "signerCollateralAccount",
+          "writable": true
+        },
+        {
+          "name": "collateralPriceUpdate"
+        },
+        {
+          "name": "debtPriceUpdate"
+        },
+        {
+          "name": "swapProgram"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "borrowAmount",
+          "type": "u64"
+        },
+        {
+          "name": "minAmountOut",
+          "type": "u64"
+        },
+        {
+          "name": "maxIterations",
+          "type": "u8"
+        },
+        {
+          "name": "targetLeverageBps",
+          "type": "u64"
+        },
+        {
+          "name": "swapData",
+          "type": "bytes"
+        }
+      ]
+    },
+    {
+      "name": "redeemReceipts",
+      "discriminator": [
+        169,
+        92,
+        129,
+        160,
+        86,
+        208,
+        193,
+        227
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bankTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "receiptMint",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "signerReceiptAccount",
+          "writable": true
+        },
+        {
+          "name": "recipientTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "shares",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "redeemShares",
+      "discriminator": [
+        239,
+        154,
+        224,
+        89,
+        240,
+        196,
+        42,
+        187
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "recipientTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bankTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "priceUpdate"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "shareAmount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "reduceBankCaps",
+      "discriminator": [
+        5,
+        198,
+        254,
+        136,
+        152,
+        174,
+        178,
+        95
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "depositCap",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "borrowCap",
+          "type": {
+            "option": "u64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "repay",
+      "discriminator": [
+        234,
+        103,
+        67,
+        82,
+        208,
+        234,
+        219,
+        166
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "repayMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bankTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "repayMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "repayMint"
+        },
+        {
+          "name": "userTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "signer"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "repayMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "borrower"
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "repayWithCollateral",
+      "discriminator": [
+        93,
+        132,
+        225,
+        226,
+        186,
+        173,
+        118,
+        111
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "collateralMint"
+        },
+        {
+          "name": "debtMint"
+        },
+        {
+          "name": "collateralBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "collateralMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "collateralTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "collateralMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "debtBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "debtMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "debtTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "debtMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "signerCollateralAccount",
+          "writable": true
+        },
+        {
+          "name": "signerDebtAccount",
+          "writable": true
+        },
+        {
+          "name": "collateralPriceUpdate"
+        },
+        {
+          "name": "debtPriceUpdate"
+        },
+        {
+          "name": "swapProgram"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "collateralAmount",
+          "type": "u64"
+        },
+        {
+          "name": "minRepayAmount",
+          "type": "u64"
+        },
+        {
+          "name": "swapData",
+          "type": "bytes"
+        }
+      ]
+    },
+    {
+      "name": "setDelegate",
+      "discriminator": [
+        242,
+        30,
+        46,
+        76,
+        108,
+        235,
+        128,
+        181
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "delegate",
+          "type": "pubkey"
+        },
+        {
+          "name": "delegateCanReceive",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "setGuardian",
+      "discriminator": [
+        147,
+        243,
+        50,
+        121,
+        154,
+        164,
+        50,
+        30
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "guardian",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "setPaused",
+      "discriminator": [
+        91,
+        60,
+        125,
+        192,
+        176,
+        225,
+        166,
+        218
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "paused",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "supply",
+      "discriminator": [
+        81,
+        67,
+        116,
+        61,
+        250,
+        209,
+        5,
+        198
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bankTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "signerTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "receiptMint",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "receiptAccount",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "swapCollateral",
+      "discriminator": [
+        163,
+        98,
+        75,
+        36,
+        36,
+        198,
+        243,
+        216
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "fromMint"
+        },
+        {
+          "name": "toMint"
+        },
+        {
+          "name": "fromBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "fromMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "fromTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "fromMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "toBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "toMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "toTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "toMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "signerFromAccount",
+          "writable": true
+        },
+        {
+          "name": "signerToAccount",
+          "writable": true
+        },
+        {
+          "name": "fromPriceUpdate"
+        },
+        {
+          "name": "toPriceUpdate"
+        },
+        {
+          "name": "swapProgram"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        },
+        {
+          "name": "minAmountOut",
+          "type": "u64"
+        },
+        {
+          "name": "swapData",
+          "type": "bytes"
+        }
+      ]
+    },
+    {
+      "name": "swapDebt",
+      "discriminator": [
+        64,
+        40,
+        49,
+        120,
+        123,
+        174,
+        240,
+        23
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "newDebtMint"
+        },
+        {
+          "name": "oldDebtMint"
+        },
+        {
+          "name": "newDebtBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "newDebtMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "newDebtTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "newDebtMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "oldDebtBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "oldDebtMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "oldDebtTokenBank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "oldDebtMint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "signerNewDebtAccount",
+          "writable": true
+        },
+        {
+          "name": "signerOldDebtAccount",
+          "writable": true
+        },
+        {
+          "name": "newDebtPriceUpdate"
+        },
+        {
+          "name": "oldDebtPriceUpdate"
+        },
+        {
+          "name": "swapProgram"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "borrowAmount",
+          "type": "u64"
+        },
+        {
+          "name": "minRepayAmount",
+          "type": "u64"
+        },
+        {
+          "name": "swapData",
+          "type": "bytes"
+        }
+      ]
+    },
+    {
+      "name": "updateBankConfig",
+      "discriminator": [
+        98,
+        195,
+        67,
+        76,
+        187,
+        143,
+        229,
+        92
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "update",
+          "type": {
+            "defined": {
+              "name": "bankConfigUpdate"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "withdraw",
+      "discriminator": [
+        183,
+        18,
+        70,
+        156,
+        148,
+        109,
+        161,
+        34
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "recipientTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "userAccount",
+          "writable": true
+        },
+        {
+          "name": "bank",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  110,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bankTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "priceUpdate"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "eventAuthority",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  95,
+                  95,
+                  101,
+                  118,
+                  101,
+                  110,
+                  116,
+                  95,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "program"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    }
+  ],
+  "accounts": [
+    {
+      "name": "bank",
+      "discriminator": [
+        142,
+        49,
+        166,
+        242,
+        50,
+        66,
+        97,
+        188
+      ]
+    },
+    {
+      "name": "priceUpdateV2",
+      "discriminator": [
+        34,
+        241,
+        35,
+        99,
+        157,
+        126,
+        244,
+        205
+      ]
+    },
+    {
+      "name": "user",
+      "discriminator": [
+        159,
+        117,
+        95,
+        227,
         239,
         151,
         58,
@@ -1144,100 +3535,1231 @@ export type LendingApp = {
       ]
     }
   ],
+  "events": [
+    {
+      "name": "bankClosedEvent",
+      "discriminator": [
+        253,
+        63,
+        195,
+        10,
+        123,
+        91,
+        246,
+        97
+      ]
+    },
+    {
+      "name": "bankConfigCancelledEvent",
+      "discriminator": [
+        29,
+        118,
+        62,
+        166,
+        188,
+        120,
+        4,
+        0
+      ]
+    },
+    {
+      "name": "bankConfigChangedEvent",
+      "discriminator": [
+        139,
+        94,
+        116,
+        6,
+        50,
+        78,
+        85,
+        177
+      ]
+    },
+    {
+      "name": "bankInitializedEvent",
+      "discriminator": [
+        126,
+        206,
+        123,
+        72,
+        87,
+        254,
+        5,
+        150
+      ]
+    },
+    {
+      "name": "bankPausedEvent",
+      "discriminator": [
+        24,
+        162,
+        75,
+        99,
+        64,
+        133,
+        149,
+        215
+      ]
+    },
+    {
+      "name": "borrowEvent",
+      "discriminator": [
+        86,
+        8,
+        140,
+        206,
+        215,
+        179,
+        118,
+        201
+      ]
+    },
+    {
+      "name": "delegateSetEvent",
+      "discriminator": [
+        137,
+        164,
+        135,
+        241,
+        166,
+        203,
+        189,
+        168
+      ]
+    },
+    {
+      "name": "depositEvent",
+      "discriminator": [
+        120,
+        248,
+        61,
+        83,
+        31,
+        142,
+        107,
+        144
+      ]
+    },
+    {
+      "name": "flashLoanEvent",
+      "discriminator": [
+        214,
+        143,
+        143,
+        217,
+        173,
+        237,
+        52,
+        26
+      ]
+    },
+    {
+      "name": "guardianSetEvent",
+      "discriminator": [
+        154,
+        146,
+        250,
+        178,
+        218,
+        199,
+        128,
+        212
+      ]
+    },
+    {
+      "name": "interestAccruedEvent",
+      "discriminator": [
+        120,
+        237,
+        172,
+        213,
+        236,
+        141,
+        136,
+        123
+      ]
+    },
+    {
+      "name": "leverageLoopEvent",
+      "discriminator": [
+        254,
+        223,
+        211,
+        15,
+        193,
+        130,
+        134,
+        13
+      ]
+    },
+    {
+      "name": "liquidateEvent",
+      "discriminator": [
+        158,
+        94,
+        144,
+        4,
+        147,
+        52,
+        5,
+        255
+      ]
+    },
+    {
+      "name": "protocolFeesCollectedEvent",
+      "discriminator": [
+        43,
+        213,
+        133,
+        149,
+        103,
+        13,
+        188,
+        38
+      ]
+    },
+    {
+      "name": "redeemEvent",
+      "discriminator": [
+        90,
+        114,
+        83,
+        146,
+        212,
+        26,
+        217,
+        59
+      ]
+    },
+    {
+      "name": "repayEvent",
+      "discriminator": [
+        129,
+        213,
+        0,
+        108,
+        218,
+        108,
+        82,
+        140
+      ]
+    },
+    {
+      "name": "supplyEvent",
+      "discriminator": [
+        102,
+        86,
+        244,
+        238,
+        146,
+        98,
+        150,
+        255
+      ]
+    },
+    {
+      "name": "userClosedEvent",
+      "discriminator": [
+        90,
+        192,
+        139,
+        28,
+        156,
+        216,
+        120,
+        169
+      ]
+    },
+    {
+      "name": "withdrawEvent",
+      "discriminator": [
+        22,
+        9,
+        133,
+        26,
+        160,
+        44,
+        71,
+        192
+      ]
+    }
+  ],
   "errors": [
     {
-      "code": 6000,
-      "name": "customError",
-      "msg": "Custom error message"
+      "code": 6000,
+      "name": "customError",
+      "msg": "Custom error message"
+    },
+    {
+      "code": 6001,
+      "name": "mathError",
+      "msg": "Underflow, Overflow error"
+    },
+    {
+      "code": 6002,
+      "name": "overBorrow",
+      "msg": "Cannot Overborrow"
+    },
+    {
+      "code": 6003,
+      "name": "mathOverflow",
+      "msg": "Variable Overflowed!"
+    },
+    {
+      "code": 6004,
+      "name": "accountIsHealthy",
+      "msg": "Account is not healthy!"
+    },
+    {
+      "code": 6005,
+      "name": "insufficientBankFunds",
+      "msg": "Insufficient funds in bank"
+    },
+    {
+      "code": 6006,
+      "name": "invalidBankState",
+      "msg": "Invalid bank state - no shares exist"
+    },
+    {
+      "code": 6007,
+      "name": "noDepositsFound",
+      "msg": "No deposits found for this user"
+    },
+    {
+      "code": 6008,
+      "name": "exceedsMaxWithdrawal",
+      "msg": "Withdrawal amount exceeds maximum withdrawable"
+    },
+    {
+      "code": 6009,
+      "name": "invalidWithdrawalAmount",
+      "msg": "Invalid withdrawal amount"
+    },
+    {
+      "code": 6010,
+      "name": "insufficientShares",
+      "msg": "Insufficient shares for withdrawal"
+    },
+    {
+      "code": 6011,
+      "name": "insufficientUserBalance",
+      "msg": "Insufficient user balance"
+    },
+    {
+      "code": 6012,
+      "name": "unauthorized",
+      "msg": "Signer is not allowed to perform this action"
+    },
+    {
+      "code": 6013,
+      "name": "bankPaused",
+      "msg": "Bank is paused"
+    },
+    {
+      "code": 6014,
+      "name": "noPendingConfig",
+      "msg": "No config change is queued"
+    },
+    {
+      "code": 6015,
+      "name": "configTimelocked",
+      "msg": "Config change is still timelocked"
+    },
+    {
+      "code": 6016,
+      "name": "depositCapExceeded",
+      "msg": "Deposit would exceed the bank's deposit cap"
+    },
+    {
+      "code": 6017,
+      "name": "borrowCapExceeded",
+      "msg": "Borrow would exceed the bank's borrow cap"
+    },
+    {
+      "code": 6018,
+      "name": "capIncrease",
+      "msg": "Caps can only be lowered"
+    },
+    {
+      "code": 6019,
+      "name": "outflowLimitExceeded",
+      "msg": "Outflow rate limit exceeded for this bank"
+    },
+    {
+      "code": 6020,
+      "name": "slippageExceeded",
+      "msg": "Required amount exceeds the given maximum"
+    },
+    {
+      "code": 6021,
+      "name": "noBorrowsFound",
+      "msg": "No borrows found for this user"
+    },
+    {
+      "code": 6022,
+      "name": "zeroShares",
+      "msg": "Deposit is too small to mint any shares"
+    },
+    {
+      "code": 6023,
+      "name": "firstDepositTooSmall",
+      "msg": "First deposit into a bank is below the minimum"
+    },
+    {
+      "code": 6024,
+      "name": "delegateRecipientNotAllowed",
+      "msg": "Delegate can only send funds to the position owner"
+    },
+    {
+      "code": 6025,
+      "name": "positionNotEmpty",
+      "msg": "Position still has deposits or borrows"
+    },
+    {
+      "code": 6026,
+      "name": "bankNotEmpty",
+      "msg": "Bank still has outstanding deposit or debt shares"
+    },
+    {
+      "code": 6027,
+      "name": "flashLoanActive",
+      "msg": "A flash loan from this bank is already active"
+    },
+    {
+      "code": 6028,
+      "name": "noFlashLoan",
+      "msg": "No active flash loan to repay"
+    },
+    {
+      "code": 6029,
+      "name": "missingFlashRepay",
+      "msg": "Flash borrow must be followed by a flash repay for the same bank"
+    },
+    {
+      "code": 6030,
+      "name": "flashLoanCpi",
+      "msg": "Flash loans cannot be taken through CPI"
+    },
+    {
+      "code": 6031,
+      "name": "invalidSwapProgram",
+      "msg": "Swap program is not enabled for this bank"
+    },
+    {
+      "code": 6032,
+      "name": "swapOutputTooLow",
+      "msg": "Swap returned less than the minimum output"
+    },
+    {
+      "code": 6033,
+      "name": "positionUnhealthy",
+      "msg": "Position would be unhealthy"
+    },
+    {
+      "code": 6034,
+      "name": "invalidLeverageParams",
+      "msg": "Leverage loop needs a non-zero borrow amount and at least one iteration"
+    },
+    {
+      "code": 6035,
+      "name": "invalidConfig",
+      "msg": "Bank config is out of bounds"
+    },
+    {
+      "code": 6036,
+      "name": "overRepay",
+      "msg": "Repay amount is more than the outstanding debt"
+    },
+    {
+      "code": 6037,
+      "name": "sameMint",
+      "msg": "Both mints are the same"
+    },
+    {
+      "code": 6038,
+      "name": "protocolFeesUncollected",
+      "msg": "Protocol fees have to be collected before the bank is closed"
+    },
+    {
+      "code": 6039,
+      "name": "tooManyPositions",
+      "msg": "Position already uses the maximum number of banks"
+    },
+    {
+      "code": 6040,
+      "name": "missingHealthAccount",
+      "msg": "Missing the bank or price account of a position"
+    },
+    {
+      "code": 6041,
+      "name": "invalidHealthAccount",
+      "msg": "Health account does not match the position"
+    }
+  ],
+  "types": [
+    {
+      "name": "bank",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "mintAddress",
+            "type": "pubkey"
+          },
+          {
+            "name": "totalDeposits",
+            "type": "u64"
+          },
+          {
+            "name": "totalDepositShares",
+            "type": "u64"
+          },
+          {
+            "name": "totalBorrowed",
+            "type": "u64"
+          },
+          {
+            "name": "totalBorrowedShares",
+            "type": "u64"
+          },
+          {
+            "name": "liquidationThreshold",
+            "type": "u64"
+          },
+          {
+            "name": "liquidationBonus",
+            "type": "u64"
+          },
+          {
+            "name": "closeFactor",
+            "type": "u64"
+          },
+          {
+            "name": "maxLtv",
+            "type": "u64"
+          },
+          {
+            "name": "interestRate",
+            "type": "u64"
+          },
+          {
+            "name": "lastUpdated",
+            "type": "i64"
+          },
+          {
+            "name": "guardian",
+            "type": "pubkey"
+          },
+          {
+            "name": "paused",
+            "type": "bool"
+          },
+          {
+            "name": "configDelay",
+            "type": "i64"
+          },
+          {
+            "name": "pendingConfig",
+            "type": {
+              "defined": {
+                "name": "bankConfigUpdate"
+              }
+            }
+          },
+          {
+            "name": "pendingConfigReadyAt",
+            "type": "i64"
+          },
+          {
+            "name": "depositCap",
+            "type": "u64"
+          },
+          {
+            "name": "borrowCap",
+            "type": "u64"
+          },
+          {
+            "name": "outflowLimit",
+            "type": "u64"
+          },
+          {
+            "name": "outflowWindow",
+            "type": "i64"
+          },
+          {
+            "name": "outflowWindowStart",
+            "type": "i64"
+          },
+          {
+            "name": "outflowPrevWindow",
+            "type": "u64"
+          },
+          {
+            "name": "outflowCurWindow",
+            "type": "u64"
+          },
+          {
+            "name": "flashLoanFee",
+            "type": "u64"
+          },
+          {
+            "name": "flashLoanAmount",
+            "type": "u64"
+          },
+          {
+            "name": "originationFee",
+            "type": "u64"
+          },
+          {
+            "name": "originationFeeProtocolShare",
+            "type": "u64"
+          },
+          {
+            "name": "protocolFees",
+            "type": "u64"
+          },
+          {
+            "name": "swapProgram",
+            "type": "pubkey"
+          },
+          {
+            "name": "minFirstDeposit",
+            "type": "u64"
+          },
+          {
+            "name": "decimals",
+            "type": "u8"
+          },
+          {
+            "name": "priceFeedId",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "bankClosedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "dust",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6001,
-      "name": "mathError",
-      "msg": "Underflow, Overflow error"
+      "name": "bankConfigCancelledEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "cancelled",
+            "type": {
+              "defined": {
+                "name": "bankConfigUpdate"
+              }
+            }
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6002,
-      "name": "overBorrow",
-      "msg": "Cannot Overborrow"
+      "name": "bankConfigChangedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "applied",
+            "type": {
+              "defined": {
+                "name": "bankConfigUpdate"
+              }
+            }
+          },
+          {
+            "name": "pending",
+            "type": {
+              "defined": {
+                "name": "bankConfigUpdate"
+              }
+            }
+          },
+          {
+            "name": "pendingReadyAt",
+            "type": "i64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6003,
-      "name": "mathOverflow",
-      "msg": "Variable Overflowed!"
+      "name": "bankConfigUpdate",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "maxLtv",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "liquidationThreshold",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "liquidationBonus",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "closeFactor",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "interestRate",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "configDelay",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "depositCap",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "borrowCap",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "outflowLimit",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "outflowWindow",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "flashLoanFee",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "originationFee",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "originationFeeProtocolShare",
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "swapProgram",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "priceFeedId",
+            "type": {
+              "option": {
+                "array": [
+                  "u8",
+                  32
+                ]
+              }
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6004,
-      "name": "accountIsHealthy",
-      "msg": "Account is not healthy!"
+      "name": "bankInitializedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "maxLtv",
+            "type": "u64"
+          },
+          {
+            "name": "liquidationThreshold",
+            "type": "u64"
+          },
+          {
+            "name": "liquidationBonus",
+            "type": "u64"
+          },
+          {
+            "name": "closeFactor",
+            "type": "u64"
+          },
+          {
+            "name": "interestRate",
+            "type": "u64"
+          },
+          {
+            "name": "configDelay",
+            "type": "i64"
+          },
+          {
+            "name": "minFirstDeposit",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "bankPausedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "paused",
+            "type": "bool"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "borrowEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "originationFee",
+            "type": "u64"
+          },
+          {
+            "name": "shares",
+            "type": "u64"
+          },
+          {
+            "name": "userBorrowed",
+            "type": "u64"
+          },
+          {
+            "name": "userBorrowedShares",
+            "type": "u64"
+          },
+          {
+            "name": "totalBorrowed",
+            "type": "u64"
+          },
+          {
+            "name": "totalBorrowedShares",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6005,
-      "name": "insufficientBankFunds",
-      "msg": "Insufficient funds in bank"
+      "name": "delegateSetEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "delegate",
+            "type": "pubkey"
+          },
+          {
+            "name": "delegateCanReceive",
+            "type": "bool"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6006,
-      "name": "invalidBankState",
-      "msg": "Invalid bank state - no shares exist"
+      "name": "depositEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "shares",
+            "type": "u64"
+          },
+          {
+            "name": "userDeposited",
+            "type": "u64"
+          },
+          {
+            "name": "userDepositedShares",
+            "type": "u64"
+          },
+          {
+            "name": "totalDeposits",
+            "type": "u64"
+          },
+          {
+            "name": "totalDepositShares",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6007,
-      "name": "noDepositsFound",
-      "msg": "No deposits found for this user"
+      "name": "flashLoanEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "fee",
+            "type": "u64"
+          },
+          {
+            "name": "totalDeposits",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6008,
-      "name": "exceedsMaxWithdrawal",
-      "msg": "Withdrawal amount exceeds maximum withdrawable"
+      "name": "guardianSetEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "guardian",
+            "type": "pubkey"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6009,
-      "name": "invalidWithdrawalAmount",
-      "msg": "Invalid withdrawal amount"
+      "name": "interestAccruedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "interest",
+            "type": "u64"
+          },
+          {
+            "name": "totalDeposits",
+            "type": "u64"
+          },
+          {
+            "name": "totalBorrowed",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6010,
-      "name": "insufficientShares",
-      "msg": "Insufficient shares for withdrawal"
+      "name": "leverageLoopEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "debtMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "collateralMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "iterations",
+            "type": "u8"
+          },
+          {
+            "name": "borrowedAmount",
+            "type": "u64"
+          },
+          {
+            "name": "originationFee",
+            "type": "u64"
+          },
+          {
+            "name": "borrowedShares",
+            "type": "u64"
+          },
+          {
+            "name": "depositedAmount",
+            "type": "u64"
+          },
+          {
+            "name": "depositedShares",
+            "type": "u64"
+          },
+          {
+            "name": "userBorrowed",
+            "type": "u64"
+          },
+          {
+            "name": "userBorrowedShares",
+            "type": "u64"
+          },
+          {
+            "name": "userDeposited",
+            "type": "u64"
+          },
+          {
+            "name": "userDepositedShares",
+            "type": "u64"
+          },
+          {
+            "name": "totalBorrowed",
+            "type": "u64"
+          },
+          {
+            "name": "totalBorrowedShares",
+            "type": "u64"
+          },
+          {
+            "name": "totalDeposits",
+            "type": "u64"
+          },
+          {
+            "name": "totalDepositShares",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6011,
-      "name": "insufficientUserBalance",
-      "msg": "Insufficient user balance"
-    }
-  ],
-  "types": [
-    {
-      "name": "bank",
+      "name": "liquidateEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "authority",
+            "name": "user",
             "type": "pubkey"
           },
           {
-            "name": "mintAddress",
+            "name": "owner",
             "type": "pubkey"
           },
           {
-            "name": "totalDeposits",
-            "type": "u64"
+            "name": "liquidator",
+            "type": "pubkey"
           },
           {
-            "name": "totalDepositShares",
+            "name": "collateralMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "borrowedMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "repaidAmount",
             "type": "u64"
           },
           {
-            "name": "totalBorrowed",
+            "name": "repaidShares",
             "type": "u64"
           },
           {
-            "name": "totalBorrowedShares",
+            "name": "seizedAmount",
             "type": "u64"
           },
           {
-            "name": "liquidationThreshold",
+            "name": "seizedShares",
             "type": "u64"
           },
           {
@@ -1245,24 +4767,72 @@ export type LendingApp = {
             "type": "u64"
           },
           {
-            "name": "closeFactor",
+            "name": "userDeposited",
             "type": "u64"
           },
           {
-            "name": "maxLtv",
+            "name": "userDepositedShares",
             "type": "u64"
           },
           {
-            "name": "interestRate",
+            "name": "userBorrowed",
             "type": "u64"
           },
           {
-            "name": "lastUpdated",
+            "name": "userBorrowedShares",
+            "type": "u64"
+          },
+          {
+            "name": "collateralTotalDeposits",
+            "type": "u64"
+          },
+          {
+            "name": "collateralTotalDepositShares",
+            "type": "u64"
+          },
+          {
+            "name": "borrowedTotalBorrowed",
+            "type": "u64"
+          },
+          {
+            "name": "borrowedTotalBorrowedShares",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
             "type": "i64"
           }
         ]
       }
     },
+    {
+      "name": "position",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "deposited",
+            "type": "u64"
+          },
+          {
+            "name": "depositedShares",
+            "type": "u64"
+          },
+          {
+            "name": "borrowed",
+            "type": "u64"
+          },
+          {
+            "name": "borrowedShares",
+            "type": "u64"
+          }
+        ]
+      }
+    },
     {
       "name": "priceFeedMessage",
       "repr": {
@@ -1372,49 +4942,226 @@ export type LendingApp = {
       }
     },
     {
-      "name": "user",
+      "name": "protocolFeesCollectedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "feeReceiver",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "protocolFees",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "redeemEvent",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "depositedSol",
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "recipient",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "shares",
+            "type": "u64"
+          },
+          {
+            "name": "totalDeposits",
             "type": "u64"
           },
           {
-            "name": "depositedSolShares",
+            "name": "totalDepositShares",
             "type": "u64"
           },
           {
-            "name": "borrowedSol",
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "repayEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
             "type": "u64"
           },
           {
-            "name": "borrowedSolShares",
+            "name": "shares",
             "type": "u64"
           },
           {
-            "name": "depositedUsdc",
+            "name": "userBorrowed",
             "type": "u64"
           },
           {
-            "name": "depositedUsdcShares",
+            "name": "userBorrowedShares",
             "type": "u64"
           },
           {
-            "name": "borrowedUsdc",
+            "name": "totalBorrowed",
             "type": "u64"
           },
           {
-            "name": "borrowedUsdcShares",
+            "name": "totalBorrowedShares",
             "type": "u64"
           },
           {
-            "name": "mintAddress",
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "supplyEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "receiptAccount",
             "type": "pubkey"
           },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "shares",
+            "type": "u64"
+          },
+          {
+            "name": "totalDeposits",
+            "type": "u64"
+          },
+          {
+            "name": "totalDepositShares",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "user",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "positions",
+            "type": {
+              "array": [
+                {
+                  "defined": {
+                    "name": "position"
+                  }
+                },
+                4
+              ]
+            }
+          },
           {
             "name": "healthFactor",
             "type": "u64"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "delegate",
+            "type": "pubkey"
+          },
+          {
+            "name": "delegateCanReceive",
+            "type": "bool"
+          },
+          {
+            "name": "index",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "userClosedEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
           }
         ]
       }
@@ -1451,6 +5198,58 @@ export type LendingApp = {
           }
         ]
       }
+    },
+    {
+      "name": "withdrawEvent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "shares",
+            "type": "u64"
+          },
+          {
+            "name": "userDeposited",
+            "type": "u64"
+          },
+          {
+            "name": "userDepositedShares",
+            "type": "u64"
+          },
+          {
+            "name": "totalDeposits",
+            "type": "u64"
+          },
+          {
+            "name": "totalDepositShares",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
     }
   ],
   "constants": [
@@ -1459,6 +5258,26 @@ export type LendingApp = {
       "type": "u64",
       "value": "100"
     },
+    {
+      "name": "maxFlashLoanFee",
+      "type": "u64",
+      "value": "1000"
+    },
+    {
+      "name": "minConfigDelay",
+      "type": "i64",
+      "value": "3600"
+    },
+    {
+      "name": "minFirstDeposit",
+      "type": "u64",
+      "value": "1000"
+    },
+    {
+      "name": "minFirstDepositDecimals",
+      "type": "u8",
+      "value": "6"
+    },
     {
       "name": "seed",
       "type": "string",
@@ -1473,6 +5292,16 @@ export type LendingApp = {
       "name": "usdcUsdFeedId",
       "type": "string",
       "value": "\"eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a\""
+    },
+    {
+      "name": "virtualDepositAssets",
+      "type": "u64",
+      "value": "1000"
+    },
+    {
+      "name": "virtualDepositShares",
+      "type": "u64",
+      "value": "1000"
     }
   ]
 };
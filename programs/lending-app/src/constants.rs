@@ -10,4 +10,32 @@ pub const SOL_USD_FEED_ID: &str="ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7b
 pub const USDC_USD_FEED_ID: &str="eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a"; 
 
 #[constant]
-pub const MAX_AGE:u64 = 100;
\ No newline at end of file
+pub const MAX_AGE:u64 = 100;
+
+// Banks a single position can hold deposits or debt in at the same time.
+// Not a #[constant], the IDL has no usize; it shows up there as the length of `User::positions`.
+pub const MAX_POSITIONS:usize = 4;
+
+// Virtual offsets added to both sides of every deposit share conversion. They make an empty bank
+// price shares 1:1 and make it expensive to inflate the share price before others deposit.
+#[constant]
+pub const VIRTUAL_DEPOSIT_SHARES:u64 = 1_000;
+
+#[constant]
+pub const VIRTUAL_DEPOSIT_ASSETS:u64 = 1_000;
+
+// Smallest deposit accepted while a bank has no deposit shares, in base units of a token with
+// MIN_FIRST_DEPOSIT_DECIMALS decimals (0.001 tokens). Each bank scales it to its mint's decimals.
+#[constant]
+pub const MIN_FIRST_DEPOSIT:u64 = 1_000;
+
+#[constant]
+pub const MIN_FIRST_DEPOSIT_DECIMALS:u8 = 6;
+
+// Shortest timelock a bank can run with, in seconds.
+#[constant]
+pub const MIN_CONFIG_DELAY:i64 = 3_600;
+
+// Highest flash loan fee a bank can charge, in bps.
+#[constant]
+pub const MAX_FLASH_LOAN_FEE:u64 = 1_000;
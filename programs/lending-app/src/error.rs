@@ -26,4 +26,64 @@ pub enum ErrorCode {
     InsufficientShares,
     #[msg("Insufficient user balance")]
     InsufficientUserBalance,
+    #[msg("Signer is not allowed to perform this action")]
+    Unauthorized,
+    #[msg("Bank is paused")]
+    BankPaused,
+    #[msg("No config change is queued")]
+    NoPendingConfig,
+    #[msg("Config change is still timelocked")]
+    ConfigTimelocked,
+    #[msg("Deposit would exceed the bank's deposit cap")]
+    DepositCapExceeded,
+    #[msg("Borrow would exceed the bank's borrow cap")]
+    BorrowCapExceeded,
+    #[msg("Caps can only be lowered")]
+    CapIncrease,
+    #[msg("Outflow rate limit exceeded for this bank")]
+    OutflowLimitExceeded,
+    #[msg("Required amount exceeds the given maximum")]
+    SlippageExceeded,
+    #[msg("No borrows found for this user")]
+    NoBorrowsFound,
+    #[msg("Deposit is too small to mint any shares")]
+    ZeroShares,
+    #[msg("First deposit into a bank is below the minimum")]
+    FirstDepositTooSmall,
+    #[msg("Delegate can only send funds to the position owner")]
+    DelegateRecipientNotAllowed,
+    #[msg("Position still has deposits or borrows")]
+    PositionNotEmpty,
+    #[msg("Bank still has outstanding deposit or debt shares")]
+    BankNotEmpty,
+    #[msg("A flash loan from this bank is already active")]
+    FlashLoanActive,
+    #[msg("No active flash loan to repay")]
+    NoFlashLoan,
+    #[msg("Flash borrow must be followed by a flash repay for the same bank")]
+    MissingFlashRepay,
+    #[msg("Flash loans cannot be taken through CPI")]
+    FlashLoanCpi,
+    #[msg("Swap program is not enabled for this bank")]
+    InvalidSwapProgram,
+    #[msg("Swap returned less than the minimum output")]
+    SwapOutputTooLow,
+    #[msg("Position would be unhealthy")]
+    PositionUnhealthy,
+    #[msg("Leverage loop needs a non-zero borrow amount and at least one iteration")]
+    InvalidLeverageParams,
+    #[msg("Bank config is out of bounds")]
+    InvalidConfig,
+    #[msg("Repay amount is more than the outstanding debt")]
+    OverRepay,
+    #[msg("Both mints are the same")]
+    SameMint,
+    #[msg("Protocol fees have to be collected before the bank is closed")]
+    ProtocolFeesUncollected,
+    #[msg("Position already uses the maximum number of banks")]
+    TooManyPositions,
+    #[msg("Missing the bank or price account of a position")]
+    MissingHealthAccount,
+    #[msg("Health account does not match the position")]
+    InvalidHealthAccount,
 }
@@ -0,0 +1,344 @@
+use anchor_lang::prelude::*;
+
+use crate::{Bank, BankConfigUpdate, User};
+
+// Events are emitted through a self CPI (`emit_cpi!`) so they survive log truncation.
+// Position events carry the state after the change, so an indexer can rebuild
+// banks and users without reading accounts.
+
+#[event]
+pub struct DepositEvent {
+    pub user:Pubkey,
+    pub owner:Pubkey,
+    pub signer:Pubkey,
+    pub mint:Pubkey,
+    pub amount:u64,
+    pub shares:u64,
+    // user's position in this mint after the deposit
+    pub user_deposited:u64,
+    pub user_deposited_shares:u64,
+    pub total_deposits:u64,
+    pub total_deposit_shares:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub user:Pubkey,
+    pub owner:Pubkey,
+    pub signer:Pubkey,
+    pub mint:Pubkey,
+    pub amount:u64,
+    pub shares:u64,
+    // user's position in this mint after the withdrawal
+    pub user_deposited:u64,
+    pub user_deposited_shares:u64,
+    pub total_deposits:u64,
+    pub total_deposit_shares:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct SupplyEvent {
+    pub signer:Pubkey,
+    pub mint:Pubkey,
+    // token account the receipts were minted to
+    pub receipt_account:Pubkey,
+    pub amount:u64,
+    pub shares:u64,
+    pub total_deposits:u64,
+    pub total_deposit_shares:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct RedeemEvent {
+    pub signer:Pubkey,
+    pub mint:Pubkey,
+    pub recipient:Pubkey,
+    pub amount:u64,
+    // receipts burned, one per deposit share
+    pub shares:u64,
+    pub total_deposits:u64,
+    pub total_deposit_shares:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct BorrowEvent {
+    pub user:Pubkey,
+    pub owner:Pubkey,
+    pub signer:Pubkey,
+    pub mint:Pubkey,
+    // tokens sent out, the debt is amount + origination_fee
+    pub amount:u64,
+    pub origination_fee:u64,
+    pub shares:u64,
+    // user's debt in this mint after the borrow
+    pub user_borrowed:u64,
+    pub user_borrowed_shares:u64,
+    pub total_borrowed:u64,
+    pub total_borrowed_shares:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct RepayEvent {
+    pub user:Pubkey,
+    pub owner:Pubkey,
+    pub signer:Pubkey,
+    pub mint:Pubkey,
+    pub amount:u64,
+    pub shares:u64,
+    // user's debt in this mint after the repayment
+    pub user_borrowed:u64,
+    pub user_borrowed_shares:u64,
+    pub total_borrowed:u64,
+    pub total_borrowed_shares:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct LiquidateEvent {
+    pub user:Pubkey,
+    pub owner:Pubkey,
+    pub liquidator:Pubkey,
+    pub collateral_mint:Pubkey,
+    pub borrowed_mint:Pubkey,
+    // debt paid by the liquidator and the debt shares it removed
+    pub repaid_amount:u64,
+    pub repaid_shares:u64,
+    // collateral sent to the liquidator, bonus included, and the deposit shares it removed
+    pub seized_amount:u64,
+    pub seized_shares:u64,
+    pub liquidation_bonus:u64,
+    // user's position after the liquidation
+    pub user_deposited:u64,
+    pub user_deposited_shares:u64,
+    pub user_borrowed:u64,
+    pub user_borrowed_shares:u64,
+    pub collateral_total_deposits:u64,
+    pub collateral_total_deposit_shares:u64,
+    pub borrowed_total_borrowed:u64,
+    pub borrowed_total_borrowed_shares:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct InterestAccruedEvent {
+    pub mint:Pubkey,
+    pub interest:u64,
+    pub total_deposits:u64,
+    pub total_borrowed:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct FlashLoanEvent {
+    pub mint:Pubkey,
+    pub signer:Pubkey,
+    pub amount:u64,
+    // fee paid on top of the amount, it goes to suppliers
+    pub fee:u64,
+    pub total_deposits:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct BankConfigChangedEvent {
+    pub mint:Pubkey,
+    pub signer:Pubkey,
+    // changes that took effect in this instruction
+    pub applied:BankConfigUpdate,
+    // changes waiting for the timelock, all of them and not only the ones added here
+    pub pending:BankConfigUpdate,
+    pub pending_ready_at:i64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct BankConfigCancelledEvent {
+    pub mint:Pubkey,
+    pub signer:Pubkey,
+    pub cancelled:BankConfigUpdate,
+    pub timestamp:i64,
+}
+
+// Summary of a leverage loop, the per-step borrows and deposits are not emitted separately.
+#[event]
+pub struct LeverageLoopEvent {
+    pub user:Pubkey,
+    pub owner:Pubkey,
+    pub signer:Pubkey,
+    pub debt_mint:Pubkey,
+    pub collateral_mint:Pubkey,
+    pub iterations:u8,
+    // totals over all iterations, the debt is borrowed_amount + origination_fee
+    pub borrowed_amount:u64,
+    pub origination_fee:u64,
+    pub borrowed_shares:u64,
+    pub deposited_amount:u64,
+    pub deposited_shares:u64,
+    // user's position after the loop
+    pub user_borrowed:u64,
+    pub user_borrowed_shares:u64,
+    pub user_deposited:u64,
+    pub user_deposited_shares:u64,
+    pub total_borrowed:u64,
+    pub total_borrowed_shares:u64,
+    pub total_deposits:u64,
+    pub total_deposit_shares:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct BankInitializedEvent {
+    pub mint:Pubkey,
+    pub authority:Pubkey,
+    pub max_ltv:u64,
+    pub liquidation_threshold:u64,
+    pub liquidation_bonus:u64,
+    pub close_factor:u64,
+    pub interest_rate:u64,
+    pub config_delay:i64,
+    pub min_first_deposit:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct ProtocolFeesCollectedEvent {
+    pub mint:Pubkey,
+    pub signer:Pubkey,
+    pub fee_receiver:Pubkey,
+    pub amount:u64,
+    // fees still owed to the protocol
+    pub protocol_fees:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct BankPausedEvent {
+    pub mint:Pubkey,
+    pub signer:Pubkey,
+    pub paused:bool,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct GuardianSetEvent {
+    pub mint:Pubkey,
+    pub signer:Pubkey,
+    pub guardian:Pubkey,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct BankClosedEvent {
+    pub mint:Pubkey,
+    pub signer:Pubkey,
+    // rounding dust swept to the authority
+    pub dust:u64,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct UserClosedEvent {
+    pub user:Pubkey,
+    pub owner:Pubkey,
+    pub timestamp:i64,
+}
+
+#[event]
+pub struct DelegateSetEvent {
+    pub user:Pubkey,
+    pub owner:Pubkey,
+    pub delegate:Pubkey,
+    pub delegate_can_receive:bool,
+    pub timestamp:i64,
+}
+
+impl DepositEvent {
+    pub fn new(user:&Account<User>, signer:Pubkey, bank:&Bank, amount:u64, shares:u64, timestamp:i64) -> Self {
+        Self {
+            user:user.key(),
+            owner:user.owner,
+            signer,
+            mint:bank.mint_address,
+            amount,
+            shares,
+            user_deposited:user.deposited(&bank.mint_address),
+            user_deposited_shares:user.deposit_shares(&bank.mint_address),
+            total_deposits:bank.total_deposits,
+            total_deposit_shares:bank.total_deposit_shares,
+            timestamp,
+        }
+    }
+}
+
+impl WithdrawEvent {
+    pub fn new(user:&Account<User>, signer:Pubkey, bank:&Bank, amount:u64, shares:u64, timestamp:i64) -> Self {
+        Self {
+            user:user.key(),
+            owner:user.owner,
+            signer,
+            mint:bank.mint_address,
+            amount,
+            shares,
+            user_deposited:user.deposited(&bank.mint_address),
+            user_deposited_shares:user.deposit_shares(&bank.mint_address),
+            total_deposits:bank.total_deposits,
+            total_deposit_shares:bank.total_deposit_shares,
+            timestamp,
+        }
+    }
+}
+
+impl BorrowEvent {
+    pub fn new(user:&Account<User>, signer:Pubkey, bank:&Bank, amount:u64, origination_fee:u64, shares:u64, timestamp:i64) -> Self {
+        Self {
+            user:user.key(),
+            owner:user.owner,
+            signer,
+            mint:bank.mint_address,
+            amount,
+            origination_fee,
+            shares,
+            user_borrowed:user.borrowed(&bank.mint_address),
+            user_borrowed_shares:user.borrow_shares(&bank.mint_address),
+            total_borrowed:bank.total_borrowed,
+            total_borrowed_shares:bank.total_borrowed_shares,
+            timestamp,
+        }
+    }
+}
+
+impl RepayEvent {
+    pub fn new(user:&Account<User>, signer:Pubkey, bank:&Bank, amount:u64, shares:u64, timestamp:i64) -> Self {
+        Self {
+            user:user.key(),
+            owner:user.owner,
+            signer,
+            mint:bank.mint_address,
+            amount,
+            shares,
+            user_borrowed:user.borrowed(&bank.mint_address),
+            user_borrowed_shares:user.borrow_shares(&bank.mint_address),
+            total_borrowed:bank.total_borrowed,
+            total_borrowed_shares:bank.total_borrowed_shares,
+            timestamp,
+        }
+    }
+}
+
+impl InterestAccruedEvent {
+    // `bank.last_updated` is the accrual time
+    pub fn new(bank:&Bank, interest:u64) -> Self {
+        Self {
+            mint:bank.mint_address,
+            interest,
+            total_deposits:bank.total_deposits,
+            total_borrowed:bank.total_borrowed,
+            timestamp:bank.last_updated,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{error::ErrorCode, Bank, BankConfigChangedEvent, BankConfigUpdate};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApplyBankConfig<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = bank.authority == signer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub bank:Account<'info,Bank>,
+    pub mint:InterfaceAccount<'info,Mint>,
+}
+
+pub fn process_apply_bank_config(ctx:Context<ApplyBankConfig>)->Result<()>{
+    let bank = &mut ctx.accounts.bank;
+
+    let clock = Clock::get()?;
+    let pending = bank.apply_pending_config(clock.unix_timestamp)?;
+
+    msg!("Queued config change applied to bank {}", bank.key());
+    emit_cpi!(BankConfigChangedEvent {
+        mint:ctx.accounts.mint.key(),
+        signer:ctx.accounts.signer.key(),
+        applied:pending,
+        pending:BankConfigUpdate::default(),
+        pending_ready_at:0,
+        timestamp:clock.unix_timestamp,
+    });
+    Ok(())
+}
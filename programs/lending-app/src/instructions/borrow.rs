@@ -1,33 +1,35 @@
 
 use anchor_lang::prelude::*;
-use anchor_spl::{ associated_token::AssociatedToken, token_2022::{transfer_checked,TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
-use pyth_solana_receiver_sdk::price_update::{ get_feed_id_from_hex, FeedId, PriceUpdateV2};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::{accrued_interest, normalize_pyth_price, Bank, User, MAX_AGE, SOL_USD_FEED_ID, USDC_USD_FEED_ID};
+use crate::{accrue_bank_interest, book_borrow, check_health, health_assets, load_health_accounts, transfer_from_treasure, BorrowEvent, HealthAsset, InterestAccruedEvent, Bank, User};
 use crate::error::ErrorCode;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Borrow<'info>{
     #[account(mut)]
     pub signer:Signer<'info>,
+    // any token account of the borrowed mint, e.g. one owned by another protocol
     #[account(
-        init_if_needed,
-        payer=signer,
-        associated_token::mint=mint,
-        associated_token::authority=signer,
-        associated_token::token_program=token_program,
+        mut,
+        token::mint=mint,
+        token::token_program=token_program,
     )]
-    pub user_wanted_token_account:InterfaceAccount<'info,TokenAccount>,
+    pub recipient_token_account:InterfaceAccount<'info,TokenAccount>,
     #[account(
         mut,
-        seeds=[b"user",signer.key().as_ref()],
-        bump
+        seeds=[b"user",user_account.owner.as_ref(),&[user_account.index]],
+        bump,
+        constraint = user_account.is_owner_or_delegate(&signer.key()) @ ErrorCode::Unauthorized,
     )]
     pub user_account:Account<'info,User>,
     #[account(
         mut,
         seeds=[b"bank",mint.key().as_ref()],
-        bump
+        bump,
+        constraint = !bank.paused @ ErrorCode::BankPaused,
     )]
     pub bank:Account<'info,Bank>,
     #[account(
@@ -39,116 +41,58 @@ pub struct Borrow<'info>{
         bump
     )]
     pub token_bank_acc:InterfaceAccount<'info, TokenAccount>,
-    pub price_update:Account<'info,PriceUpdateV2>,
+    pub price_update:Box<Account<'info,PriceUpdateV2>>,
     pub mint:InterfaceAccount<'info,Mint>,
     pub token_program:Interface<'info,TokenInterface>,
-    pub associated_token_program :Program<'info,AssociatedToken>,
     pub system_program:Program<'info,System>,
+    // remaining accounts: a (bank, price update) pair for each other bank the position uses,
+    // see load_health_accounts
 }
-//  1. Calculate the collateral of the user by LTV - Done
-//  2. Calculate Accrued Interest of the user - Done
-//  3. Calculate the real time price of the collateral asset using PYTH oracle..
-//  4. Calculate borrowable amount using max_ltv and collateral realtime price.
-//  5. Transfer the amount from bank to the user.
-
-pub fn process_borrow(ctx:Context<Borrow>,amount:u64)->Result<()>{
-    let bank = &mut ctx.accounts.bank;
-    let user_account= &mut ctx.accounts.user_account;
-    let total_collateral:u64;
-    let borrowed_amount_in_usd:u128;
-    // Step 3 -> Real time price using pyth oracle.
-    if ctx.accounts.mint.key() == user_account.mint_address.key() {
-        // Calculate SOL collateral if user wants to borrow usdc
-        msg!("Inside the USDC area");
-        let feed_id:FeedId = get_feed_id_from_hex(SOL_USD_FEED_ID)?;
-        let borrowed_feed_id:FeedId = get_feed_id_from_hex(USDC_USD_FEED_ID)?;
-        
-        // let price = ctx.accounts.price_update.get_price_no_older_than(&Clock::get()?, MAX_AGE, &feed_id)?;
-        // let borrowed_price = ctx.accounts.price_update.get_price_no_older_than(&Clock::get()?, MAX_AGE, &borrowed_feed_id)?;
-        
-        // let _normalised_price = normalize_pyth_price(price.price, price.exponent)?;
-        // let borrowed_normalised_price = normalize_pyth_price(borrowed_price.price, borrowed_price.exponent)?;
-        // msg!("normalised_price:{}",borrowed_normalised_price);
-
-        total_collateral = user_account.deposited_sol;
-        // borrowed_amount_in_usd = (amount as u128).checked_mul(borrowed_normalised_price).ok_or(ErrorCode::MathOverflow)?;
-        
-    } else {
-        msg!("Inside the sol area");
-        // Calculate USDC collateral if user wants to borrow sol
-        let feed_id:FeedId = get_feed_id_from_hex(USDC_USD_FEED_ID)?;
-        let borrowed_feed_id:FeedId = get_feed_id_from_hex(SOL_USD_FEED_ID)?;
-
-        // let price = ctx.accounts.price_update.get_price_no_older_than(&Clock::get()?, MAX_AGE, &feed_id)?;
-        // let borrowed_price = ctx.accounts.price_update.get_price_no_older_than(&Clock::get()?, MAX_AGE, &borrowed_feed_id)?;
+// 1. Accrue the bank so the position is valued on up to date shares.
+// 2. Book the borrow, the origination fee is added to the debt.
+// 3. The position must stay within max_ltv of its collateral, fee included.
+// 4. Transfer the amount from the bank to the recipient.
 
-        // let _normalised_price = normalize_pyth_price(price.price, price.exponent)?;
-        // let borrowed_normalised_price = normalize_pyth_price(borrowed_price.price, borrowed_price.exponent)?;
-        // msg!("normalised_price:{}",borrowed_normalised_price);
-        
-        total_collateral = user_account.deposited_usdc;
+pub fn process_borrow(mut ctx:Context<Borrow>,amount:u64)->Result<()>{
+    let accounts = &mut ctx.accounts;
+    let interest = accrue_bank_interest(&mut accounts.bank)?;
 
-    }
-
-    msg!("total collateral: {}", total_collateral);
-    
-    // NOT USING PYTH PRICING VARIABLES BECAUSE THIS IS ON DEVNET AND DEVNET TOKENS DOES NOT HOLD REAL VALUE. 
-
-
-    let borrowable_amount = total_collateral
-    .checked_mul(bank.max_ltv)
-    .unwrap()
-    / 10_000u64;         
-    
-    msg!("borrowable_amount: {}", borrowable_amount);
+    let user_account = &mut accounts.user_account;
+    user_account.check_recipient(&accounts.signer.key(), &accounts.recipient_token_account.owner)?;
+    let mint_key = accounts.mint.key();
 
-  if amount > borrowable_amount {
-       return Err(ErrorCode::OverBorrow.into());
-  }
-  let mint_key = ctx.accounts.mint.key();
+    // Origination fee is added to the debt, not taken out of the borrowed tokens,
+    // so it counts against the borrowing limit.
+    let (origination_fee, amount_in_shares) = book_borrow(&mut accounts.bank, user_account, &mint_key, amount)?;
+    let (others, _) = load_health_accounts(&accounts.user_account, &[mint_key], ctx.remaining_accounts)?;
+    check_health(
+        &accounts.user_account,
+        &health_assets(&[HealthAsset { bank: &accounts.bank, price_update: &accounts.price_update }], &others),
+    )?;
+    let timestamp = Clock::get()?;
+    accounts.bank.record_outflow(amount, timestamp.unix_timestamp)?;
 
-  let signer_seeds:&[&[&[u8]]] = &[&[
-       b"treasure",
-        mint_key.as_ref(),
-        &[ctx.bumps.token_bank_acc],
-  ]];
-  let cpi_context = CpiContext::new_with_signer(
-     ctx.accounts.token_program.to_account_info(),
-     TransferChecked {
-        authority:ctx.accounts.token_bank_acc.to_account_info(),
-        from:ctx.accounts.token_bank_acc.to_account_info(),
-        to:ctx.accounts.user_wanted_token_account.to_account_info(),
-        mint:ctx.accounts.mint.to_account_info(),
-    }, 
-     signer_seeds
-    );
-    transfer_checked(cpi_context, amount, ctx.accounts.mint.decimals)?;
+    transfer_from_treasure(
+        &accounts.token_program,
+        &accounts.token_bank_acc,
+        accounts.recipient_token_account.to_account_info(),
+        &accounts.mint,
+        ctx.bumps.token_bank_acc,
+        amount,
+    )?;
 
-    // Step 6. Updating Bank & User's state.
-    let amount_in_shares = if bank.total_borrowed == 0 || bank.total_borrowed_shares == 0 {
-        amount // 1:1 mapping at start
-    } else {
-        amount
-        .checked_mul(bank.total_borrowed_shares)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(bank.total_borrowed)
-        .ok_or(ErrorCode::MathOverflow)?
-    };
-    // total borrowed shares 
-    bank.total_borrowed = bank.total_borrowed.checked_add(amount).unwrap();
-    bank.total_borrowed_shares = bank.total_borrowed_shares.checked_add(amount_in_shares).unwrap();
-    let timestamp = Clock::get()?;
-    bank.last_updated = timestamp.unix_timestamp;
-    //  update the user's state
-    if ctx.accounts.mint.key() == user_account.mint_address.key() {
-         msg!("Updating USDC borrowed amounts");
-         user_account.borrowed_usdc = user_account.borrowed_usdc.checked_add(amount).unwrap();
-         user_account.borrowed_usdc_shares = user_account.borrowed_usdc_shares.checked_add(amount_in_shares).unwrap();
-    } else {
-         msg!("Updating SOL borrowed amounts");
-        user_account.borrowed_sol = user_account.borrowed_sol.checked_add(amount).unwrap();
-        user_account.borrowed_sol_shares =  user_account.borrowed_sol_shares.checked_add(amount_in_shares).unwrap();
+    if interest > 0 {
+        emit_cpi!(InterestAccruedEvent::new(&ctx.accounts.bank, interest));
     }
+    emit_cpi!(BorrowEvent::new(
+        &ctx.accounts.user_account,
+        ctx.accounts.signer.key(),
+        &ctx.accounts.bank,
+        amount,
+        origination_fee,
+        amount_in_shares,
+        timestamp.unix_timestamp,
+    ));
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{error::ErrorCode, Bank, BankConfigCancelledEvent, BankConfigUpdate};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelBankConfig<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = bank.authority == signer.key() || bank.guardian == signer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub bank:Account<'info,Bank>,
+    pub mint:InterfaceAccount<'info,Mint>,
+}
+
+// Dropping a queued change only ever keeps risk where it is,
+// so the guardian is allowed to do it as well.
+pub fn process_cancel_bank_config(ctx:Context<CancelBankConfig>)->Result<()>{
+    let bank = &mut ctx.accounts.bank;
+
+    if bank.pending_config.is_empty() {
+        return Err(ErrorCode::NoPendingConfig.into());
+    }
+
+    let cancelled = bank.pending_config;
+    bank.pending_config = BankConfigUpdate::default();
+    bank.pending_config_ready_at = 0;
+
+    msg!("Queued config change cancelled for bank {}", bank.key());
+    emit_cpi!(BankConfigCancelledEvent {
+        mint:ctx.accounts.mint.key(),
+        signer:ctx.accounts.signer.key(),
+        cancelled,
+        timestamp:Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
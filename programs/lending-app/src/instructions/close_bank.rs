@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_2022::{close_account, transfer_checked, CloseAccount, TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::{error::ErrorCode, Bank, BankClosedEvent};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseBank<'info> {
+    #[account(mut)]
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = bank.authority == signer.key() @ ErrorCode::Unauthorized,
+        close=signer
+    )]
+    pub bank:Account<'info,Bank>,
+    #[account(
+        mut,
+        seeds=[b"treasure",mint.key().as_ref()],
+        token::mint=mint,
+        token::authority=bank_token_account,
+        token::token_program=token_program,
+        bump
+    )]
+    pub bank_token_account:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        seeds=[b"receipt",mint.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint:InterfaceAccount<'info,Mint>,
+    // receives whatever dust is left in the vault
+    #[account(
+        mut,
+        token::mint=mint,
+        token::token_program=token_program,
+    )]
+    pub authority_token_account:InterfaceAccount<'info,TokenAccount>,
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+// 1. Make sure nobody holds deposit or debt shares anymore and the protocol fees were collected.
+// 2. Sweep rounding dust (value owned by the virtual shares) to the authority.
+// 3. Close the treasure vault, the receipt mint and the bank, returning rent to the authority.
+
+pub fn process_close_bank(ctx:Context<CloseBank>)->Result<()>{
+    let bank = &ctx.accounts.bank;
+
+    if bank.total_deposit_shares != 0
+        || bank.total_borrowed_shares != 0
+        || bank.total_borrowed != 0
+        || bank.flash_loan_amount != 0
+        || ctx.accounts.receipt_mint.supply != 0
+    {
+        return Err(ErrorCode::BankNotEmpty.into());
+    }
+    // Otherwise the fees would leave with the dust and never show up as collected.
+    if bank.protocol_fees != 0 {
+        return Err(ErrorCode::ProtocolFeesUncollected.into());
+    }
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds:&[&[&[u8]]] = &[&[
+        b"treasure",
+        mint_key.as_ref(),
+        &[ctx.bumps.bank_token_account],
+    ]];
+
+    let dust = ctx.accounts.bank_token_account.amount;
+    if dust > 0 {
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from:ctx.accounts.bank_token_account.to_account_info(),
+                to:ctx.accounts.authority_token_account.to_account_info(),
+                authority:ctx.accounts.bank_token_account.to_account_info(),
+                mint:ctx.accounts.mint.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked(cpi_context, dust, ctx.accounts.mint.decimals)?;
+    }
+
+    let close_context = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account:ctx.accounts.bank_token_account.to_account_info(),
+            destination:ctx.accounts.signer.to_account_info(),
+            authority:ctx.accounts.bank_token_account.to_account_info(),
+        },
+        signer_seeds,
+    );
+    close_account(close_context)?;
+
+    // the bank is the receipt mint's close authority
+    let bank_seeds:&[&[&[u8]]] = &[&[
+        b"bank",
+        mint_key.as_ref(),
+        &[ctx.bumps.bank],
+    ]];
+    let close_context = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account:ctx.accounts.receipt_mint.to_account_info(),
+            destination:ctx.accounts.signer.to_account_info(),
+            authority:bank.to_account_info(),
+        },
+        bank_seeds,
+    );
+    close_account(close_context)?;
+
+    msg!("Closed bank {} (swept {} dust)", bank.key(), dust);
+    emit_cpi!(BankClosedEvent {
+        mint:mint_key,
+        signer:ctx.accounts.signer.key(),
+        dust,
+        timestamp:Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
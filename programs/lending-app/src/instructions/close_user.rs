@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, Position, User, UserClosedEvent};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseUser<'info> {
+    #[account(mut)]
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"user",signer.key().as_ref(),&[user_account.index]],
+        bump,
+        close=signer
+    )]
+    pub user_account:Account<'info,User>,
+}
+
+// Rent goes back to the owner once nothing is deposited or borrowed anymore.
+pub fn process_close_user(ctx:Context<CloseUser>)->Result<()>{
+    let user = &ctx.accounts.user_account;
+    if !user.positions.iter().all(Position::is_empty) {
+        return Err(ErrorCode::PositionNotEmpty.into());
+    }
+    msg!("Closed user account {} of {}", user.key(), user.owner);
+    emit_cpi!(UserClosedEvent {
+        user:user.key(),
+        owner:user.owner,
+        timestamp:Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_2022::{transfer_checked, TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::{error::ErrorCode, Bank, ProtocolFeesCollectedEvent};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = bank.authority == signer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub bank:Account<'info,Bank>,
+    #[account(
+        mut,
+        seeds=[b"treasure",mint.key().as_ref()],
+        token::mint=mint,
+        token::authority=bank_token_account,
+        token::token_program=token_program,
+        bump
+    )]
+    pub bank_token_account:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        token::mint=mint,
+        token::token_program=token_program,
+    )]
+    pub fee_receiver:InterfaceAccount<'info,TokenAccount>,
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+// Sends the protocol's accrued fees to `fee_receiver`. Fees that are still part of
+// outstanding debt stay in `protocol_fees` until the vault holds enough to pay them.
+pub fn process_collect_protocol_fees(ctx:Context<CollectProtocolFees>)->Result<()>{
+    let bank = &mut ctx.accounts.bank;
+
+    let amount = bank.protocol_fees.min(ctx.accounts.bank_token_account.amount);
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds:&[&[&[u8]]] = &[&[
+        b"treasure",
+        mint_key.as_ref(),
+        &[ctx.bumps.bank_token_account],
+    ]];
+    let cpi_context = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from:ctx.accounts.bank_token_account.to_account_info(),
+            to:ctx.accounts.fee_receiver.to_account_info(),
+            authority:ctx.accounts.bank_token_account.to_account_info(),
+            mint:ctx.accounts.mint.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer_checked(cpi_context, amount, ctx.accounts.mint.decimals)?;
+
+    bank.protocol_fees = bank.protocol_fees.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+    msg!("Collected {} protocol fees from bank {}", amount, bank.key());
+    emit_cpi!(ProtocolFeesCollectedEvent {
+        mint:mint_key,
+        signer:ctx.accounts.signer.key(),
+        fee_receiver:ctx.accounts.fee_receiver.key(),
+        amount,
+        protocol_fees:ctx.accounts.bank.protocol_fees,
+        timestamp:Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -1,8 +1,9 @@
 use anchor_lang::{prelude::*};
-use anchor_spl::{ token_2022::{transfer_checked,TransferChecked} , token_interface::{Mint, TokenAccount, TokenInterface}};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{Bank, User};
+use crate::{accrue_bank_interest, book_deposit, transfer_to_treasure, DepositEvent, InterestAccruedEvent, deposit_amount_to_shares, deposit_shares_to_amount, error::ErrorCode, Bank, Rounding, User};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
@@ -10,7 +11,8 @@ pub struct Deposit<'info> {
     #[account(
         mut,
         seeds=[b"bank",token_mint_address.key().as_ref()],
-        bump
+        bump,
+        constraint = !bank.paused @ ErrorCode::BankPaused,
     )]
     pub bank:Account<'info,Bank>,
     #[account(
@@ -22,9 +24,11 @@ pub struct Deposit<'info> {
         bump
     )]
     pub token_bank_acc:InterfaceAccount<'info,TokenAccount>,
+    // wallet the deposit is credited to, the signer only pays for it
+    pub beneficiary:SystemAccount<'info>,
     #[account(
         mut,
-        seeds=[b"user",signer.key().as_ref()],
+        seeds=[b"user",beneficiary.key().as_ref(),&[user_lending_program_acc.index]],
         bump
     )]
     pub user_lending_program_acc:Account<'info,User>,
@@ -36,46 +40,67 @@ pub struct Deposit<'info> {
     )]
     pub user_token_account:InterfaceAccount<'info,TokenAccount>,
     pub token_mint_address:InterfaceAccount<'info,Mint>,
-    pub token_program: Interface<'info, TokenInterface>,
     pub token_program_2022: Interface<'info, TokenInterface>,
 }
 
-pub fn process_deposit(mut ctx:Context<Deposit>, amount:u64)->Result<()>{
-    let account = &mut ctx.accounts;
-    // CPI -> Transfer the user's funds into the bank's vault. 
-    let ix = CpiContext::new(
-        account.token_program_2022.to_account_info(),
-        TransferChecked {
-        from:account.user_token_account.to_account_info(),
-        to:account.token_bank_acc.to_account_info(),
-        authority:account.signer.to_account_info(),
-        mint:account.token_mint_address.to_account_info()
-    });
-    transfer_checked(ix, amount, account.token_mint_address.decimals)?;
-  let users_deposit_shares = if account.bank.total_deposits == 0 {
-        // First deposit in the bank - user gets 1:1 shares 
-        amount
-    } else {
-        // Calculate proportional shares: (amount * existing_shares) / existing_deposits
-        amount
-            .checked_mul(account.bank.total_deposit_shares)
-            .unwrap()
-            .checked_div(account.bank.total_deposits)
-            .unwrap()
-    };
+pub fn process_deposit(ctx:Context<Deposit>, amount:u64)->Result<()>{
+    let interest = accrue_bank_interest(&mut ctx.accounts.bank)?;
+    if interest > 0 {
+        emit_cpi!(InterestAccruedEvent::new(&ctx.accounts.bank, interest));
+    }
+    let bank = &ctx.accounts.bank;
+    // Shares minted to the user round down.
+    let users_deposit_shares = deposit_amount_to_shares(bank, amount, Rounding::Down)?;
+    deposit_into_bank(ctx, amount, users_deposit_shares)
+}
 
-    match account.token_mint_address.to_account_info().key() {
-        key if key == account.user_lending_program_acc.mint_address.key() =>{
-            account.user_lending_program_acc.deposited_usdc = account.user_lending_program_acc.deposited_usdc.checked_add(amount).unwrap();
-            account.user_lending_program_acc.deposited_usdc_shares = account.user_lending_program_acc.deposited_usdc_shares.checked_add(users_deposit_shares).unwrap();
-        },
-        _ =>{
-            account.user_lending_program_acc.deposited_sol = account.user_lending_program_acc.deposited_sol.checked_add(amount).unwrap();
-            account.user_lending_program_acc.deposited_sol_shares = account.user_lending_program_acc.deposited_sol_shares.checked_add(users_deposit_shares).unwrap();
-        }
+// Mint exactly `shares` deposit shares, pulling at most `max_amount` tokens from the user.
+// The amount is rounded up so the bank is never short of what the shares are worth.
+pub fn process_deposit_exact_shares(ctx:Context<Deposit>, shares:u64, max_amount:u64)->Result<()>{
+    let interest = accrue_bank_interest(&mut ctx.accounts.bank)?;
+    if interest > 0 {
+        emit_cpi!(InterestAccruedEvent::new(&ctx.accounts.bank, interest));
+    }
+    let bank = &ctx.accounts.bank;
+    let amount = deposit_shares_to_amount(bank, shares, Rounding::Up)?;
+    if amount > max_amount {
+        return Err(ErrorCode::SlippageExceeded.into());
     }
-    // Handling total deposit shares
-        account.bank.total_deposits = account.bank.total_deposits.checked_add(amount).unwrap();
-        account.bank.total_deposit_shares = account.bank.total_deposit_shares.checked_add(users_deposit_shares).unwrap();
+    deposit_into_bank(ctx, amount, shares)
+}
+
+fn deposit_into_bank(mut ctx:Context<Deposit>, amount:u64, users_deposit_shares:u64)->Result<()>{
+    let account = &mut ctx.accounts;
+    let mint_key = account.token_mint_address.key();
+    // Share inflation protection: never mint zero shares, and seed an empty bank with a meaningful deposit.
+    book_deposit(&mut account.bank, &mut account.user_lending_program_acc, &mint_key, amount, users_deposit_shares)?;
+    account.bank.record_inflow(amount, Clock::get()?.unix_timestamp);
+
+    // CPI -> Transfer the user's funds into the bank's vault.
+    transfer_to_treasure(
+        &account.token_program_2022,
+        account.user_token_account.to_account_info(),
+        &account.token_bank_acc,
+        account.signer.to_account_info(),
+        &account.token_mint_address,
+        amount,
+    )?;
+
+    msg!(
+        "Deposit completed: Payer: {}, Beneficiary: {}, Amount: {}, Shares minted: {}",
+        account.signer.key(),
+        account.beneficiary.key(),
+        amount,
+        users_deposit_shares
+    );
+
+    emit_cpi!(DepositEvent::new(
+        &ctx.accounts.user_lending_program_acc,
+        ctx.accounts.signer.key(),
+        &ctx.accounts.bank,
+        amount,
+        users_deposit_shares,
+        ctx.accounts.bank.last_updated,
+    ));
     Ok(())
-}
\ No newline at end of file
+}
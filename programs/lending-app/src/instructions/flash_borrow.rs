@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use anchor_lang::Discriminator;
+use anchor_spl::{token_2022::{transfer_checked, TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::{error::ErrorCode, instruction, Bank};
+
+#[derive(Accounts)]
+pub struct FlashBorrow<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = !bank.paused @ ErrorCode::BankPaused,
+    )]
+    pub bank:Account<'info,Bank>,
+    #[account(
+        mut,
+        seeds=[b"treasure",mint.key().as_ref()],
+        token::mint=mint,
+        token::authority=bank_token_account,
+        token::token_program=token_program,
+        bump
+    )]
+    pub bank_token_account:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        token::mint=mint,
+        token::token_program=token_program,
+    )]
+    pub recipient_token_account:InterfaceAccount<'info,TokenAccount>,
+    pub mint:InterfaceAccount<'info,Mint>,
+    /// CHECK: address is checked against the instructions sysvar id
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions:UncheckedAccount<'info>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+// Positions of the bank, vault and mint in `FlashRepay`'s account list.
+const REPAY_BANK_INDEX:usize = 1;
+const REPAY_VAULT_INDEX:usize = 2;
+const REPAY_MINT_INDEX:usize = 4;
+
+// A repay only counts if it names this bank, vault and mint in their own slots. Matching the
+// bank key anywhere in the list would accept a repay to another bank with this one tacked on.
+fn is_matching_repay(ix:&Instruction, bank:&Pubkey, vault:&Pubkey, mint:&Pubkey)->bool{
+    let account_at = |index:usize| ix.accounts.get(index).map(|meta| meta.pubkey);
+    ix.program_id == crate::ID
+        && ix.data.starts_with(instruction::FlashRepay::DISCRIMINATOR)
+        && account_at(REPAY_BANK_INDEX) == Some(*bank)
+        && account_at(REPAY_VAULT_INDEX) == Some(*vault)
+        && account_at(REPAY_MINT_INDEX) == Some(*mint)
+}
+
+// 1. Make sure this is a top level instruction and no other flash loan is open on the bank.
+// 2. Look ahead in the transaction for a `flash_repay` on the same bank.
+// 3. Lend the tokens out of the treasure vault.
+
+pub fn process_flash_borrow(ctx:Context<FlashBorrow>, amount:u64)->Result<()>{
+    let bank = &mut ctx.accounts.bank;
+    let instructions = ctx.accounts.instructions.to_account_info();
+
+    if bank.flash_loan_amount != 0 {
+        return Err(ErrorCode::FlashLoanActive.into());
+    }
+    if amount == 0 || amount > ctx.accounts.bank_token_account.amount {
+        return Err(ErrorCode::InsufficientBankFunds.into());
+    }
+
+    // When invoked through CPI the current top level instruction belongs to another program.
+    let current_index = load_current_index_checked(&instructions)? as usize;
+    let current_ix = load_instruction_at_checked(current_index, &instructions)?;
+    if current_ix.program_id != crate::ID {
+        return Err(ErrorCode::FlashLoanCpi.into());
+    }
+
+    let bank_key = bank.key();
+    let vault_key = ctx.accounts.bank_token_account.key();
+    let mint_key = ctx.accounts.mint.key();
+    let mut index = current_index + 1;
+    let mut repay_found = false;
+    while let Ok(ix) = load_instruction_at_checked(index, &instructions) {
+        if is_matching_repay(&ix, &bank_key, &vault_key, &mint_key) {
+            repay_found = true;
+            break;
+        }
+        index += 1;
+    }
+    if !repay_found {
+        return Err(ErrorCode::MissingFlashRepay.into());
+    }
+
+    // Counted like any other outflow, `flash_repay` gives the room back.
+    bank.record_outflow(amount, Clock::get()?.unix_timestamp)?;
+
+    let signer_seeds:&[&[&[u8]]] = &[&[
+        b"treasure",
+        mint_key.as_ref(),
+        &[ctx.bumps.bank_token_account],
+    ]];
+    let cpi_context = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from:ctx.accounts.bank_token_account.to_account_info(),
+            to:ctx.accounts.recipient_token_account.to_account_info(),
+            authority:ctx.accounts.bank_token_account.to_account_info(),
+            mint:ctx.accounts.mint.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer_checked(cpi_context, amount, ctx.accounts.mint.decimals)?;
+
+    bank.flash_loan_amount = amount;
+    msg!("Flash borrow: Bank: {}, Amount: {}", bank_key, amount);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{InstructionData, ToAccountMetas};
+    use anchor_lang::solana_program::instruction::AccountMeta;
+
+    use super::*;
+    use crate::accounts;
+
+    struct Market {
+        bank:Pubkey,
+        vault:Pubkey,
+        mint:Pubkey,
+    }
+
+    fn market()->Market {
+        Market { bank:Pubkey::new_unique(), vault:Pubkey::new_unique(), mint:Pubkey::new_unique() }
+    }
+
+    fn repay_ix(market:&Market)->Instruction {
+        let metas = accounts::FlashRepay {
+            signer:Pubkey::new_unique(),
+            bank:market.bank,
+            bank_token_account:market.vault,
+            user_token_account:Pubkey::new_unique(),
+            mint:market.mint,
+            token_program:anchor_spl::token_2022::ID,
+            event_authority:Pubkey::new_unique(),
+            program:crate::ID,
+        }
+        .to_account_metas(None);
+        Instruction { program_id:crate::ID, accounts:metas, data:instruction::FlashRepay {}.data() }
+    }
+
+    #[test]
+    fn repay_for_the_same_bank_matches() {
+        let a = market();
+        assert!(is_matching_repay(&repay_ix(&a), &a.bank, &a.vault, &a.mint));
+    }
+
+    #[test]
+    fn repay_for_another_bank_does_not_match() {
+        let (a, b) = (market(), market());
+        // repay bank B, with bank A's accounts appended as extra accounts
+        let mut ix = repay_ix(&b);
+        for key in [a.bank, a.vault, a.mint] {
+            ix.accounts.push(AccountMeta::new(key, false));
+        }
+        assert!(!is_matching_repay(&ix, &a.bank, &a.vault, &a.mint));
+    }
+
+    #[test]
+    fn repay_with_a_foreign_vault_or_mint_does_not_match() {
+        let a = market();
+        let mut ix = repay_ix(&a);
+        ix.accounts[REPAY_VAULT_INDEX].pubkey = Pubkey::new_unique();
+        assert!(!is_matching_repay(&ix, &a.bank, &a.vault, &a.mint));
+
+        let mut ix = repay_ix(&a);
+        ix.accounts[REPAY_MINT_INDEX].pubkey = Pubkey::new_unique();
+        assert!(!is_matching_repay(&ix, &a.bank, &a.vault, &a.mint));
+    }
+
+    #[test]
+    fn other_instructions_do_not_match() {
+        let a = market();
+        let mut ix = repay_ix(&a);
+        ix.data = instruction::FlashBorrow { amount:1 }.data();
+        assert!(!is_matching_repay(&ix, &a.bank, &a.vault, &a.mint));
+
+        let mut ix = repay_ix(&a);
+        ix.program_id = Pubkey::new_unique();
+        assert!(!is_matching_repay(&ix, &a.bank, &a.vault, &a.mint));
+    }
+}
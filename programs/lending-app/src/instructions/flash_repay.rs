@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_2022::{transfer_checked, TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::{error::ErrorCode, mul_div, Bank, FlashLoanEvent, Rounding};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump
+    )]
+    pub bank:Account<'info,Bank>,
+    #[account(
+        mut,
+        seeds=[b"treasure",mint.key().as_ref()],
+        token::mint=mint,
+        token::authority=bank_token_account,
+        token::token_program=token_program,
+        bump
+    )]
+    pub bank_token_account:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        token::mint=mint,
+        token::token_program=token_program,
+    )]
+    pub user_token_account:InterfaceAccount<'info,TokenAccount>,
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+// Pays back the open flash loan plus `flash_loan_fee`, the fee goes to suppliers.
+pub fn process_flash_repay(ctx:Context<FlashRepay>)->Result<()>{
+    let bank = &mut ctx.accounts.bank;
+
+    let amount = bank.flash_loan_amount;
+    if amount == 0 {
+        return Err(ErrorCode::NoFlashLoan.into());
+    }
+    let fee = mul_div(amount, bank.flash_loan_fee as u128, 10_000, Rounding::Up)?;
+    let total = amount.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from:ctx.accounts.user_token_account.to_account_info(),
+            to:ctx.accounts.bank_token_account.to_account_info(),
+            authority:ctx.accounts.signer.to_account_info(),
+            mint:ctx.accounts.mint.to_account_info(),
+        },
+    );
+    transfer_checked(cpi_context, total, ctx.accounts.mint.decimals)?;
+
+    bank.total_deposits = bank.total_deposits.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+    bank.flash_loan_amount = 0;
+    let timestamp = Clock::get()?.unix_timestamp;
+    bank.record_inflow(total, timestamp);
+
+    msg!("Flash repay: Bank: {}, Amount: {}, Fee: {}", bank.key(), amount, fee);
+    emit_cpi!(FlashLoanEvent {
+        mint:ctx.accounts.mint.key(),
+        signer:ctx.accounts.signer.key(),
+        amount,
+        fee,
+        total_deposits:ctx.accounts.bank.total_deposits,
+        timestamp,
+    });
+    Ok(())
+}
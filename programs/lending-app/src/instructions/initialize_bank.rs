@@ -1,8 +1,16 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
+use anchor_spl::token_2022::{
+    initialize_mint2,
+    spl_token_2022::{extension::ExtensionType, state::Mint as MintState},
+    InitializeMint2,
+};
+use anchor_spl::token_2022_extensions::{mint_close_authority_initialize, MintCloseAuthorityInitialize};
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::Bank;
+use crate::{error::ErrorCode, Bank, BankConfigUpdate, BankInitializedEvent, MIN_FIRST_DEPOSIT, MIN_FIRST_DEPOSIT_DECIMALS};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeBank<'info> {
     #[account(mut)]
@@ -24,38 +32,150 @@ pub struct InitializeBank<'info> {
         bump
     )]
     pub token_bank_acc:InterfaceAccount<'info,TokenAccount>,
+    // receipts for `supply`, 1 token per deposit share
+    /// CHECK: created in the handler together with its MintCloseAuthority extension
+    #[account(
+        mut,
+        seeds=[b"receipt",token_mint_address.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint:UncheckedAccount<'info>,
     pub token_mint_address:InterfaceAccount<'info,Mint>,
     pub system_program:Program<'info,System>,
+    // receipts need Token-2022 extensions
+    #[account(address = anchor_spl::token_2022::ID)]
     pub token_program:Interface<'info,TokenInterface>
 
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn process_init_bank(
     ctx: Context<InitializeBank>,
     max_ltv:u64,
-    mint_address:Pubkey,
     liquidation_threshold:u64,
     liquidation_bonus:u64,
     close_factor:u64,
-    interest_rate:u64
-
+    interest_rate:u64,
+    config_delay:i64,
+    price_feed_id:[u8;32],
     ) -> Result<()> {
     let bank_account = &mut ctx.accounts.bank;
     bank_account.set_inner(Bank {
         authority:ctx.accounts.signer.key(),
-        close_factor:close_factor,
-        liquidation_threshold:liquidation_threshold,
-        liquidation_bonus:liquidation_bonus,
+        close_factor,
+        liquidation_threshold,
+        liquidation_bonus,
         last_updated:0,
-        max_ltv:max_ltv,
-        mint_address:mint_address,
+        max_ltv,
+        mint_address:ctx.accounts.token_mint_address.key(),
         total_deposit_shares:0,
         total_deposits:0,
-        interest_rate:interest_rate,
+        interest_rate,
         total_borrowed:0,
-        total_borrowed_shares:0
+        total_borrowed_shares:0,
+        guardian:ctx.accounts.signer.key(),
+        paused:false,
+        config_delay,
+        pending_config:BankConfigUpdate::default(),
+        pending_config_ready_at:0,
+        deposit_cap:u64::MAX,
+        borrow_cap:u64::MAX,
+        outflow_limit:u64::MAX,
+        outflow_window:0,
+        outflow_window_start:0,
+        outflow_prev_window:0,
+        outflow_cur_window:0,
+        flash_loan_fee:0,
+        flash_loan_amount:0,
+        origination_fee:0,
+        origination_fee_protocol_share:0,
+        protocol_fees:0,
+        swap_program:Pubkey::default(),
+        min_first_deposit:scaled_min_first_deposit(ctx.accounts.token_mint_address.decimals)?,
+        decimals:ctx.accounts.token_mint_address.decimals,
+        price_feed_id,
+    });
+    bank_account.validate_config()?;
+
+    create_receipt_mint(&ctx)?;
+
+    let bank = &ctx.accounts.bank;
+    emit_cpi!(BankInitializedEvent {
+        mint:bank.mint_address,
+        authority:bank.authority,
+        max_ltv:bank.max_ltv,
+        liquidation_threshold:bank.liquidation_threshold,
+        liquidation_bonus:bank.liquidation_bonus,
+        close_factor:bank.close_factor,
+        interest_rate:bank.interest_rate,
+        config_delay:bank.config_delay,
+        min_first_deposit:bank.min_first_deposit,
+        timestamp:Clock::get()?.unix_timestamp,
     });
     Ok(())
 }
 
+// MIN_FIRST_DEPOSIT converted to `decimals`, never less than one base unit.
+fn scaled_min_first_deposit(decimals:u8) -> Result<u64> {
+    let scale = 10_u64
+        .checked_pow(decimals.abs_diff(MIN_FIRST_DEPOSIT_DECIMALS) as u32)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let amount = if decimals >= MIN_FIRST_DEPOSIT_DECIMALS {
+        MIN_FIRST_DEPOSIT.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?
+    } else {
+        MIN_FIRST_DEPOSIT / scale
+    };
+    Ok(amount.max(1))
+}
+
+// Receipts are plain transferable tokens: whoever holds them can redeem the shares they stand for,
+// see redeem_receipts. The bank can close the mint once every receipt is burned, see close_bank.
+fn create_receipt_mint(ctx:&Context<InitializeBank>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let mint_key = accounts.token_mint_address.key();
+    let bank_key = accounts.bank.key();
+    let signer_seeds:&[&[&[u8]]] = &[&[
+        b"receipt",
+        mint_key.as_ref(),
+        &[ctx.bumps.receipt_mint],
+    ]];
+
+    let space = ExtensionType::try_calculate_account_len::<MintState>(&[ExtensionType::MintCloseAuthority])?;
+    create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            CreateAccount {
+                from:accounts.signer.to_account_info(),
+                to:accounts.receipt_mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &accounts.token_program.key(),
+    )?;
+
+    mint_close_authority_initialize(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            MintCloseAuthorityInitialize {
+                token_program_id:accounts.token_program.to_account_info(),
+                mint:accounts.receipt_mint.to_account_info(),
+            },
+        ),
+        Some(&bank_key),
+    )?;
+    initialize_mint2(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            InitializeMint2 {
+                mint:accounts.receipt_mint.to_account_info(),
+            },
+        ),
+        accounts.token_mint_address.decimals,
+        &bank_key,
+        None,
+    )
+}
+
 
@@ -1,38 +1,33 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Mint, TokenInterface};
 
-use crate::User;
+use crate::{Position, User, MAX_POSITIONS};
 
 #[derive(Accounts)]
+#[instruction(index:u8)]
 pub struct InitializeUser<'info> {
     #[account(mut)]
     pub signer:Signer<'info>,
     #[account(
         init,
         payer=signer,
-        seeds=[b"user",signer.key().as_ref()],
+        seeds=[b"user",signer.key().as_ref(),&[index]],
         space= 8 + User::INIT_SPACE ,
         bump
     )]
     pub user_account:Account<'info,User>,
-    pub mint_address:InterfaceAccount<'info,Mint>,
     pub system_program : Program<'info,System>,
-    pub token_program: Interface<'info, TokenInterface>
 }
 
-pub fn process_init_user(ctx:Context<InitializeUser>)-> Result<()> {
+// A wallet can open several isolated positions, one per `index`.
+pub fn process_init_user(ctx:Context<InitializeUser>, index:u8)-> Result<()> {
     let user = &mut ctx.accounts.user_account;
     user.set_inner(User {
-        borrowed_sol:0,
-        borrowed_sol_shares:0,
-        borrowed_usdc:0,
-        borrowed_usdc_shares:0,
-        deposited_sol:0,
-        deposited_sol_shares:0,
-        deposited_usdc:0,
-        deposited_usdc_shares:0,
+        positions:[Position::default(); MAX_POSITIONS],
         health_factor:0,
-        mint_address:ctx.accounts.mint_address.key()
+        owner:ctx.accounts.signer.key(),
+        delegate:Pubkey::default(),
+        delegate_can_receive:false,
+        index,
     });
     Ok(())
 }
\ No newline at end of file
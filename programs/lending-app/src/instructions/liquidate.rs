@@ -1,18 +1,24 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token_2022::{transfer_checked, TransferChecked};
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use pyth_solana_receiver_sdk::price_update::{self, get_feed_id_from_hex, PriceUpdateV2};
-
-use crate::{normalize_pyth_price, Bank, User, MAX_AGE, SOL_USD_FEED_ID, USDC_USD_FEED_ID};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+    accrue_bank_interest, asset_price, book_repay, book_withdraw, borrow_amount_to_shares, borrow_shares_to_amount,
+    deposit_amount_to_shares, deposit_shares_to_amount, health_assets, load_health_accounts, mul_div, position_value, token_amount,
+    token_value, transfer_from_treasure, transfer_to_treasure, Bank, HealthAsset, InterestAccruedEvent,
+    LiquidateEvent, Rounding, User,
+};
 use crate::error::ErrorCode;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Liquidate<'info> {
     #[account(mut)]
     pub signer:Signer<'info>,
-    pub collateral_mint:InterfaceAccount<'info,Mint>,
-    pub borrowed_mint:InterfaceAccount<'info,Mint>,
+    pub collateral_mint:Box<InterfaceAccount<'info,Mint>>,
+    #[account(constraint = borrowed_mint.key() != collateral_mint.key() @ ErrorCode::SameMint)]
+    pub borrowed_mint:Box<InterfaceAccount<'info,Mint>>,
     #[account(
         mut,
         seeds=[b"bank",collateral_mint.key().as_ref()],
@@ -27,19 +33,20 @@ pub struct Liquidate<'info> {
         seeds=[b"treasure",collateral_mint.key().as_ref()],
         bump
     )]
-    pub collateral_token_bank:InterfaceAccount<'info,TokenAccount>,
+    pub collateral_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
+    // the position being liquidated, it holds both the collateral and the debt
     #[account(
         mut,
-        seeds=[b"user",signer.key().as_ref()],
+        seeds=[b"user",user_account.owner.as_ref(),&[user_account.index]],
         bump
     )]
-    pub user_collateral_token_account:Box<Account<'info,User>>,
+    pub user_account:Box<Account<'info,User>>,
      #[account(
         mut,
         seeds=[b"bank",borrowed_mint.key().as_ref()],
         bump
     )]
-    pub borrowed_bank:Account<'info,Bank>,
+    pub borrowed_bank:Box<Account<'info,Bank>>,
     #[account(
         mut,
         token::mint=borrowed_mint,
@@ -48,20 +55,14 @@ pub struct Liquidate<'info> {
         seeds=[b"treasure",borrowed_mint.key().as_ref()],
         bump
     )]
-    pub borrowed_token_bank:InterfaceAccount<'info,TokenAccount>,
-       #[account(
-        mut,
-        seeds=[b"user",signer.key().as_ref()],
-        bump
-    )]
-    pub user_borrowed_token_account:Box<Account<'info,User>>,
+    pub borrowed_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
     #[account(
         mut,
         associated_token::mint=borrowed_mint,
         associated_token::authority = signer,
         associated_token::token_program = token_program,
     )]
-    pub liquidator_borrowed_token_account:InterfaceAccount<'info,TokenAccount>,
+    pub liquidator_borrowed_token_account:Box<InterfaceAccount<'info,TokenAccount>>,
     #[account(
         init_if_needed, 
         payer = signer,
@@ -69,113 +70,147 @@ pub struct Liquidate<'info> {
         associated_token::authority = signer,
         associated_token::token_program = token_program,
     )]
-    pub liquidator_colleteral_token_account:InterfaceAccount<'info,TokenAccount>,
-    pub price_update:Box<Account<'info,PriceUpdateV2>>,
+    pub liquidator_colleteral_token_account:Box<InterfaceAccount<'info,TokenAccount>>,
+    // price accounts for the two mints, a Pyth price account holds a single feed
+    pub collateral_price_update:Box<Account<'info,PriceUpdateV2>>,
+    pub borrowed_price_update:Box<Account<'info,PriceUpdateV2>>,
     pub token_program:Interface<'info,TokenInterface>,
     pub system_program:Program<'info,System>,
     pub associated_token_program: Program<'info, AssociatedToken>, 
+    // remaining accounts: a (bank, price update) pair for each other bank the position uses,
+    // see load_health_accounts
 }
 
-
-
-pub fn process_liquidate(ctx:Context<Liquidate>)-> Result<()> {
-
-    let collateral_bank = &mut ctx.accounts.collateral_bank;
-    let borrowed_bank = &mut ctx.accounts.borrowed_bank;
-    let user_collateral_acc = &mut ctx.accounts.user_collateral_token_account;
-    let user_borrowed_acc = &mut ctx.accounts.user_borrowed_token_account;
-
-    // Total Collateral Value
-    let total_collateral:u64;
-    let total_borrowed:u64;
-
-    let deposited_usdc=  user_collateral_acc.deposited_usdc;
-    let deposited_sol =  user_collateral_acc.deposited_sol;
-
-    let borrowed_usdc=  user_borrowed_acc.borrowed_usdc;
-    let borrowed_sol =  user_borrowed_acc.borrowed_sol;
-
-    // FEED ID's
-    let sol_feed_id = get_feed_id_from_hex(SOL_USD_FEED_ID)?;
-    let usdc_feed_id = get_feed_id_from_hex(USDC_USD_FEED_ID)?;
-
-    let sol_price_object = &ctx.accounts.price_update.get_price_no_older_than(&Clock::get()?, MAX_AGE, &sol_feed_id)?;
-    let usdc_price_object = &ctx.accounts.price_update.get_price_no_older_than(&Clock::get()?, MAX_AGE, &usdc_feed_id)?;
-   
-    let sol_price = normalize_pyth_price(sol_price_object.price, sol_price_object.exponent)?;
-    let usdc_price = normalize_pyth_price(usdc_price_object.price, usdc_price_object.exponent)?;
-    // collateral
-    let usdc_collateral = (deposited_usdc as u128).checked_mul(usdc_price).ok_or(ErrorCode::MathOverflow)?; 
-    let sol_collateral = (deposited_sol as u128).checked_mul(usdc_price).ok_or(ErrorCode::MathOverflow)?; 
-    // borrowed
-    let usdc_borrowed = (borrowed_usdc as u128).checked_mul(sol_price).ok_or(ErrorCode::MathOverflow)?; 
-    let sol_borrowed = (borrowed_sol as u128).checked_mul(sol_price).ok_or(ErrorCode::MathOverflow)?; 
-
-    total_collateral = (usdc_collateral as u64).checked_add(sol_collateral as u64).unwrap();
-    total_borrowed = (usdc_borrowed as u64).checked_add(sol_borrowed as u64).unwrap();
-    // Calculating Health Factor
-    let health_factor = total_collateral.checked_mul(collateral_bank.liquidation_threshold).unwrap().checked_div(total_borrowed).unwrap();
-
-    // If acc is not healthy, throw Error.
-    if health_factor >= 0{
+// 1. Accrue both banks and value the position from its shares.
+// 2. The position is liquidatable once its debt is above the collateral weighted by liquidation_threshold.
+// 3. The liquidator repays up to close_factor of the debt and receives that value in collateral plus
+//    liquidation_bonus, capped at what the user has deposited.
+// 4. Burn the repaid debt shares and the seized deposit shares.
+
+pub fn process_liquidate(mut ctx:Context<Liquidate>)-> Result<()> {
+    let accounts = &mut ctx.accounts;
+
+    let collateral_interest = accrue_bank_interest(&mut accounts.collateral_bank)?;
+    let borrowed_interest = accrue_bank_interest(&mut accounts.borrowed_bank)?;
+    let interest_events = [
+        InterestAccruedEvent::new(&accounts.collateral_bank, collateral_interest),
+        InterestAccruedEvent::new(&accounts.borrowed_bank, borrowed_interest),
+    ];
+
+    let collateral_key = accounts.collateral_mint.key();
+    let borrowed_key = accounts.borrowed_mint.key();
+    let collateral_decimals = accounts.collateral_mint.decimals;
+    let borrowed_decimals = accounts.borrowed_mint.decimals;
+
+    let (others, _) = load_health_accounts(&accounts.user_account, &[collateral_key, borrowed_key], ctx.remaining_accounts)?;
+    let value = position_value(
+        &accounts.user_account,
+        &health_assets(
+            &[
+                HealthAsset { bank: &accounts.collateral_bank, price_update: &accounts.collateral_price_update },
+                HealthAsset { bank: &accounts.borrowed_bank, price_update: &accounts.borrowed_price_update },
+            ],
+            &others,
+        ),
+    )?;
+    if value.debt <= value.liquidation_limit {
         return Err(ErrorCode::AccountIsHealthy.into());
     }
-    // Calculating Liquidation Amount 
-    let liquidation_amount = total_collateral.checked_mul(borrowed_bank.close_factor).unwrap().checked_div(10000).unwrap();
-
-    let cpi_context_instruction = CpiContext::new(
-    ctx.accounts.token_program.to_account_info(), 
-    TransferChecked {
-       from:ctx.accounts.liquidator_borrowed_token_account.to_account_info(),
-       to:ctx.accounts.borrowed_token_bank.to_account_info(),
-       authority:ctx.accounts.borrowed_mint.to_account_info(),
-       mint:ctx.accounts.borrowed_mint.to_account_info(),
-    });
-    // Transfer Liquidators money to Bank etc.
-    transfer_checked(cpi_context_instruction, liquidation_amount, ctx.accounts.borrowed_mint.decimals)?;
-
-    let mint_key = ctx.accounts.collateral_mint.key();
-
-    let signer_seeds:&[&[&[u8]]] = &[&[
-        b"treasure",
-        mint_key.as_ref(),
-        &[ctx.bumps.collateral_token_bank]
-    ]];
-    // Calculating Liquidation Bonus and transferring it to the liquidator as an incentive.
-    let liquidation_bonus = liquidation_amount.checked_mul(collateral_bank.liquidation_bonus).unwrap().checked_div(10000).unwrap();
-    let liquidate_total= liquidation_amount.checked_add(liquidation_bonus).unwrap();
-
-    let cpi_context_ix = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        TransferChecked {
-            from:ctx.accounts.collateral_token_bank.to_account_info(),
-            to:ctx.accounts.liquidator_colleteral_token_account.to_account_info(),
-            mint:ctx.accounts.collateral_mint.to_account_info(),
-            authority:ctx.accounts.collateral_token_bank.to_account_info(),
-        },
-        signer_seeds
-    );
-    // transferring the liquidation amount and liquidation bonus from the collateral bank.
-    transfer_checked(cpi_context_ix,liquidate_total , ctx.accounts.collateral_mint.decimals)?;
-
-    // Handles the user's state and bank's state.
-    if  user_collateral_acc.mint_address.key() == collateral_bank.mint_address.key() {             // user's collateral is USDC
-        // decrement user's collateral and also decrement the user's debt USDC
-        // liquidator receievd the usdc incentive bonus for paying off debt
-        user_collateral_acc.deposited_usdc =user_collateral_acc.deposited_usdc.checked_sub(liquidation_bonus).unwrap();
-        // liquidator paid the sol amount
-        user_collateral_acc.borrowed_sol = user_collateral_acc.borrowed_sol.checked_sub(liquidation_amount).unwrap();
-
-        collateral_bank.total_deposits = collateral_bank.total_deposits.checked_sub(liquidation_bonus).unwrap();
-        borrowed_bank.total_borrowed = borrowed_bank.total_borrowed.checked_sub(liquidation_amount).unwrap();
 
+    let debt_shares = accounts.user_account.borrow_shares(&borrowed_key);
+    let collateral_shares = accounts.user_account.deposit_shares(&collateral_key);
+    if debt_shares == 0 {
+        return Err(ErrorCode::NoBorrowsFound.into());
+    }
+    if collateral_shares == 0 {
+        return Err(ErrorCode::NoDepositsFound.into());
+    }
+    let debt = borrow_shares_to_amount(&accounts.borrowed_bank, debt_shares, Rounding::Up)?;
+    let collateral = deposit_shares_to_amount(&accounts.collateral_bank, collateral_shares, Rounding::Down)?;
+
+    // Value the repaid debt in collateral tokens and add the bonus on top
+    let collateral_price = asset_price(&accounts.collateral_bank, &accounts.collateral_price_update)?;
+    let borrowed_price = asset_price(&accounts.borrowed_bank, &accounts.borrowed_price_update)?;
+    let bonus_bps = 10_000 + accounts.collateral_bank.liquidation_bonus as u128;
+
+    let mut repaid_amount = mul_div(debt, accounts.borrowed_bank.close_factor as u128, 10_000, Rounding::Down)?;
+    let repaid_value = token_value(repaid_amount, &borrowed_price, borrowed_decimals)?;
+    let repaid_in_collateral = token_amount(repaid_value, &collateral_price, collateral_decimals)?;
+    let mut seized_amount = mul_div(repaid_in_collateral, bonus_bps, 10_000, Rounding::Down)?;
+
+    // Not enough collateral left for the full close factor: seize all of it and repay proportionally less
+    if seized_amount > collateral {
+        repaid_amount = mul_div(repaid_amount, collateral as u128, seized_amount as u128, Rounding::Up)?;
+        seized_amount = collateral;
+    }
+    let liquidation_bonus = seized_amount - mul_div(seized_amount, 10_000, bonus_bps, Rounding::Up)?;
+
+    // Repaid debt shares round down and seized deposit shares round up, both in favour of the bank
+    let repaid_shares = if repaid_amount >= debt {
+        debt_shares
+    } else {
+        borrow_amount_to_shares(&accounts.borrowed_bank, repaid_amount, Rounding::Down)?
+    };
+    let seized_shares = if seized_amount == collateral {
+        collateral_shares
     } else {
-        // decrement user's collateral and also decrement the user's debt SOL                                   // user's collateral is SOL
-           user_collateral_acc.deposited_sol =  user_collateral_acc.deposited_sol.checked_sub(liquidation_bonus).unwrap();
-        // liquidator paid the usdc amount
-           user_collateral_acc.borrowed_usdc =  user_collateral_acc.borrowed_usdc.checked_sub(liquidation_amount).unwrap();
-           collateral_bank.total_deposits =  collateral_bank.total_deposits.checked_sub(liquidation_bonus).unwrap();
-           borrowed_bank.total_borrowed = borrowed_bank.total_borrowed.checked_sub(liquidation_amount).unwrap();
+        deposit_amount_to_shares(&accounts.collateral_bank, seized_amount, Rounding::Up)?.min(collateral_shares)
+    };
+    if repaid_shares == 0 || seized_shares == 0 {
+        return Err(ErrorCode::ZeroShares.into());
     }
+
+    book_repay(&mut accounts.borrowed_bank, &mut accounts.user_account, &borrowed_key, repaid_amount, repaid_shares)?;
+    book_withdraw(&mut accounts.collateral_bank, &mut accounts.user_account, &collateral_key, seized_amount, seized_shares)?;
+
+    // Seized collateral leaves the vault like any withdrawal, the repaid debt comes back in.
+    let now = Clock::get()?.unix_timestamp;
+    accounts.collateral_bank.record_outflow(seized_amount, now)?;
+    accounts.borrowed_bank.record_inflow(repaid_amount, now);
+
+    transfer_to_treasure(
+        &accounts.token_program,
+        accounts.liquidator_borrowed_token_account.to_account_info(),
+        &accounts.borrowed_token_bank,
+        accounts.signer.to_account_info(),
+        &accounts.borrowed_mint,
+        repaid_amount,
+    )?;
+    transfer_from_treasure(
+        &accounts.token_program,
+        &accounts.collateral_token_bank,
+        accounts.liquidator_colleteral_token_account.to_account_info(),
+        &accounts.collateral_mint,
+        ctx.bumps.collateral_token_bank,
+        seized_amount,
+    )?;
+
+    let user = &accounts.user_account;
+    let event = LiquidateEvent {
+        user:user.key(),
+        owner:user.owner,
+        liquidator:accounts.signer.key(),
+        collateral_mint:collateral_key,
+        borrowed_mint:borrowed_key,
+        repaid_amount,
+        repaid_shares,
+        seized_amount,
+        seized_shares,
+        liquidation_bonus,
+        user_deposited:user.deposited(&collateral_key),
+        user_deposited_shares:user.deposit_shares(&collateral_key),
+        user_borrowed:user.borrowed(&borrowed_key),
+        user_borrowed_shares:user.borrow_shares(&borrowed_key),
+        collateral_total_deposits:accounts.collateral_bank.total_deposits,
+        collateral_total_deposit_shares:accounts.collateral_bank.total_deposit_shares,
+        borrowed_total_borrowed:accounts.borrowed_bank.total_borrowed,
+        borrowed_total_borrowed_shares:accounts.borrowed_bank.total_borrowed_shares,
+        timestamp:now,
+    };
+
+    for interest_event in interest_events.into_iter().filter(|event| event.interest > 0) {
+        emit_cpi!(interest_event);
+    }
+    emit_cpi!(event);
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,231 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+    begin_swap, book_borrow, book_deposit, check_health, deposit_amount_to_shares, error::ErrorCode,
+    health_assets, load_health_accounts, position_value, swap_via_adapter, transfer_from_treasure,
+    transfer_to_treasure, Bank, HealthAsset, LeverageLoopEvent, Rounding, User,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LoopLeverage<'info> {
+    #[account(mut)]
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"user",user_account.owner.as_ref(),&[user_account.index]],
+        bump,
+        constraint = user_account.is_owner_or_delegate(&signer.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub user_account:Box<Account<'info,User>>,
+    pub debt_mint:Box<InterfaceAccount<'info,Mint>>,
+    #[account(constraint = collateral_mint.key() != debt_mint.key() @ ErrorCode::SameMint)]
+    pub collateral_mint:Box<InterfaceAccount<'info,Mint>>,
+    #[account(
+        mut,
+        seeds=[b"bank",debt_mint.key().as_ref()],
+        bump,
+        constraint = !debt_bank.paused @ ErrorCode::BankPaused,
+    )]
+    pub debt_bank:Box<Account<'info,Bank>>,
+    #[account(
+        mut,
+        seeds=[b"treasure",debt_mint.key().as_ref()],
+        token::mint=debt_mint,
+        token::authority=debt_token_bank,
+        token::token_program = token_program,
+        bump
+    )]
+    pub debt_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        seeds=[b"bank",collateral_mint.key().as_ref()],
+        bump,
+        constraint = !collateral_bank.paused @ ErrorCode::BankPaused,
+    )]
+    pub collateral_bank:Box<Account<'info,Bank>>,
+    #[account(
+        mut,
+        seeds=[b"treasure",collateral_mint.key().as_ref()],
+        token::mint=collateral_mint,
+        token::authority=collateral_token_bank,
+        token::token_program = token_program,
+        bump
+    )]
+    pub collateral_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
+    // borrowed tokens land here and are the input of each swap
+    #[account(
+        mut,
+        token::mint=debt_mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_debt_account:Box<InterfaceAccount<'info,TokenAccount>>,
+    // each swap's output lands here and is deposited as collateral
+    #[account(
+        mut,
+        token::mint=collateral_mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_collateral_account:Box<InterfaceAccount<'info,TokenAccount>>,
+    // price accounts for the two mints, a Pyth price account holds a single feed
+    pub collateral_price_update:Box<Account<'info,PriceUpdateV2>>,
+    pub debt_price_update:Box<Account<'info,PriceUpdateV2>>,
+    /// CHECK: must be the debt bank's configured swap program, checked in the handler
+    pub swap_program:UncheckedAccount<'info>,
+    pub token_program:Interface<'info,TokenInterface>
+    // remaining accounts: a (bank, price update) pair for each other bank the position uses,
+    // see load_health_accounts, then the accounts of the swap
+}
+
+// Every iteration:
+// 1. Borrow `borrow_amount` from the debt bank to the signer.
+// 2. Swap it to the collateral mint, each swap must return at least `min_amount_out`.
+// 3. Deposit the output into the collateral bank.
+// The loop stops after `max_iterations` or once leverage reaches `target_leverage_bps` (0 = no target).
+// Intermediate steps may exceed max_ltv, only the final position has to be healthy.
+
+pub fn process_loop_leverage<'info>(
+    mut ctx:Context<'_, '_, 'info, 'info, LoopLeverage<'info>>,
+    borrow_amount:u64,
+    min_amount_out:u64,
+    max_iterations:u8,
+    target_leverage_bps:u64,
+    swap_data:Vec<u8>,
+) -> Result<()> {
+    if max_iterations == 0 || borrow_amount == 0 {
+        return Err(ErrorCode::InvalidLeverageParams.into());
+    }
+
+    let accounts = &mut ctx.accounts;
+    let interest_events = begin_swap(
+        &accounts.user_account,
+        &accounts.signer.key(),
+        &accounts.swap_program.key(),
+        &mut accounts.debt_bank,
+        &mut accounts.collateral_bank,
+    )?;
+
+    let debt_key = accounts.debt_mint.key();
+    let collateral_key = accounts.collateral_mint.key();
+    let now = Clock::get()?.unix_timestamp;
+    let (others, adapter_accounts) = load_health_accounts(&accounts.user_account, &[debt_key, collateral_key], ctx.remaining_accounts)?;
+    let signer = accounts.signer.key();
+
+    let mut iterations = 0;
+    let mut total_borrowed = 0_u64;
+    let mut total_fees = 0_u64;
+    let mut total_borrow_shares = 0_u64;
+    let mut total_deposited = 0_u64;
+    let mut total_deposit_shares = 0_u64;
+    while iterations < max_iterations {
+        let (origination_fee, borrow_shares) = book_borrow(&mut accounts.debt_bank, &mut accounts.user_account, &debt_key, borrow_amount)?;
+        accounts.debt_bank.record_outflow(borrow_amount, now)?;
+        transfer_from_treasure(
+            &accounts.token_program,
+            &accounts.debt_token_bank,
+            accounts.signer_debt_account.to_account_info(),
+            &accounts.debt_mint,
+            ctx.bumps.debt_token_bank,
+            borrow_amount,
+        )?;
+
+        let swapped = swap_via_adapter(
+            &accounts.swap_program.to_account_info(),
+            adapter_accounts,
+            &swap_data,
+            borrow_amount,
+            min_amount_out,
+            &mut accounts.signer_collateral_account,
+        )?;
+
+        let shares = deposit_amount_to_shares(&accounts.collateral_bank, swapped, Rounding::Down)?;
+        book_deposit(&mut accounts.collateral_bank, &mut accounts.user_account, &collateral_key, swapped, shares)?;
+        transfer_to_treasure(
+            &accounts.token_program,
+            accounts.signer_collateral_account.to_account_info(),
+            &accounts.collateral_token_bank,
+            accounts.signer.to_account_info(),
+            &accounts.collateral_mint,
+            swapped,
+        )?;
+        accounts.collateral_bank.record_inflow(swapped, now);
+
+        iterations += 1;
+        total_borrowed = total_borrowed.checked_add(borrow_amount).ok_or(ErrorCode::MathOverflow)?;
+        total_fees = total_fees.checked_add(origination_fee).ok_or(ErrorCode::MathOverflow)?;
+        total_borrow_shares = total_borrow_shares.checked_add(borrow_shares).ok_or(ErrorCode::MathOverflow)?;
+        total_deposited = total_deposited.checked_add(swapped).ok_or(ErrorCode::MathOverflow)?;
+        total_deposit_shares = total_deposit_shares.checked_add(shares).ok_or(ErrorCode::MathOverflow)?;
+
+        if target_leverage_bps > 0 {
+            let value = position_value(
+                &accounts.user_account,
+                &health_assets(
+                    &[
+                        HealthAsset { bank: &accounts.collateral_bank, price_update: &accounts.collateral_price_update },
+                        HealthAsset { bank: &accounts.debt_bank, price_update: &accounts.debt_price_update },
+                    ],
+                    &others,
+                ),
+            )?;
+            // leverage = collateral / (collateral - debt)
+            let equity = value.collateral.saturating_sub(value.debt);
+            if equity == 0 || value.collateral * 10_000 / equity >= target_leverage_bps as u128 {
+                break;
+            }
+        }
+    }
+
+    check_health(
+        &accounts.user_account,
+        &health_assets(
+            &[
+                HealthAsset { bank: &accounts.collateral_bank, price_update: &accounts.collateral_price_update },
+                HealthAsset { bank: &accounts.debt_bank, price_update: &accounts.debt_price_update },
+            ],
+            &others,
+        ),
+    )?;
+
+    msg!(
+        "Leverage loop completed: User: {}, Iterations: {}, Borrowed: {}, Deposited: {}",
+        accounts.user_account.key(),
+        iterations,
+        total_borrowed,
+        total_deposited
+    );
+
+    let user = &accounts.user_account;
+    let summary = LeverageLoopEvent {
+        user:user.key(),
+        owner:user.owner,
+        signer,
+        debt_mint:debt_key,
+        collateral_mint:collateral_key,
+        iterations,
+        borrowed_amount:total_borrowed,
+        origination_fee:total_fees,
+        borrowed_shares:total_borrow_shares,
+        deposited_amount:total_deposited,
+        deposited_shares:total_deposit_shares,
+        user_borrowed:user.borrowed(&debt_key),
+        user_borrowed_shares:user.borrow_shares(&debt_key),
+        user_deposited:user.deposited(&collateral_key),
+        user_deposited_shares:user.deposit_shares(&collateral_key),
+        total_borrowed:accounts.debt_bank.total_borrowed,
+        total_borrowed_shares:accounts.debt_bank.total_borrowed_shares,
+        total_deposits:accounts.collateral_bank.total_deposits,
+        total_deposit_shares:accounts.collateral_bank.total_deposit_shares,
+        timestamp:now,
+    };
+    for event in interest_events.into_iter().filter(|event| event.interest > 0) {
+        emit_cpi!(event);
+    }
+    emit_cpi!(summary);
+
+    Ok(())
+}
@@ -13,6 +13,12 @@ pub use deposit::*;
 pub mod withdraw;
 pub use withdraw::*;
 
+pub mod supply;
+pub use supply::*;
+
+pub mod redeem_receipts;
+pub use redeem_receipts::*;
+
 pub mod borrow;
 pub use borrow::*;
 
@@ -23,4 +29,55 @@ pub mod repay;
 pub use repay::*;
 
 pub mod liquidate;
-pub use liquidate::*;
\ No newline at end of file
+pub use liquidate::*;
+
+pub mod set_guardian;
+pub use set_guardian::*;
+
+pub mod set_paused;
+pub use set_paused::*;
+
+pub mod update_bank_config;
+pub use update_bank_config::*;
+
+pub mod apply_bank_config;
+pub use apply_bank_config::*;
+
+pub mod cancel_bank_config;
+pub use cancel_bank_config::*;
+
+pub mod reduce_bank_caps;
+pub use reduce_bank_caps::*;
+
+pub mod set_delegate;
+pub use set_delegate::*;
+
+pub mod close_user;
+pub use close_user::*;
+
+pub mod close_bank;
+pub use close_bank::*;
+
+pub mod flash_borrow;
+pub use flash_borrow::*;
+
+pub mod flash_repay;
+pub use flash_repay::*;
+
+pub mod collect_protocol_fees;
+pub use collect_protocol_fees::*;
+
+pub mod swap_adapter;
+pub use swap_adapter::*;
+
+pub mod repay_with_collateral;
+pub use repay_with_collateral::*;
+
+pub mod loop_leverage;
+pub use loop_leverage::*;
+
+pub mod swap_collateral;
+pub use swap_collateral::*;
+
+pub mod swap_debt;
+pub use swap_debt::*;
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{burn, Burn};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    accrue_bank_interest, book_redeem, deposit_shares_to_amount, error::ErrorCode, transfer_from_treasure, Bank,
+    InterestAccruedEvent, RedeemEvent, Rounding,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemReceipts<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = !bank.paused @ ErrorCode::BankPaused,
+    )]
+    pub bank:Account<'info,Bank>,
+    #[account(
+        mut,
+        seeds=[b"treasure",mint.key().as_ref()],
+        token::mint=mint,
+        token::authority=bank_token_account,
+        token::token_program=token_program,
+        bump
+    )]
+    pub bank_token_account:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        seeds=[b"receipt",mint.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint:InterfaceAccount<'info,Mint>,
+    // the receipts are burned from here, whoever holds them can redeem them
+    #[account(
+        mut,
+        token::mint=receipt_mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_receipt_account:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        token::mint=mint,
+        token::token_program=token_program,
+    )]
+    pub recipient_token_account:InterfaceAccount<'info,TokenAccount>,
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+// Burns `shares` receipts held by the signer and pays what the shares are worth to `recipient_token_account`.
+pub fn process_redeem_receipts(mut ctx:Context<RedeemReceipts>, shares:u64)->Result<()>{
+    let interest = accrue_bank_interest(&mut ctx.accounts.bank)?;
+    if interest > 0 {
+        emit_cpi!(InterestAccruedEvent::new(&ctx.accounts.bank, interest));
+    }
+    let accounts = &mut ctx.accounts;
+
+    if shares == 0 {
+        return Err(ErrorCode::InvalidWithdrawalAmount.into());
+    }
+    if shares > accounts.signer_receipt_account.amount {
+        return Err(ErrorCode::InsufficientShares.into());
+    }
+    // Amounts paid out round down.
+    let amount = deposit_shares_to_amount(&accounts.bank, shares, Rounding::Down)?;
+    if amount == 0 {
+        return Err(ErrorCode::InvalidWithdrawalAmount.into());
+    }
+
+    accounts.bank.record_outflow(amount, Clock::get()?.unix_timestamp)?;
+    book_redeem(&mut accounts.bank, amount, shares)?;
+
+    burn(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Burn {
+                mint:accounts.receipt_mint.to_account_info(),
+                from:accounts.signer_receipt_account.to_account_info(),
+                authority:accounts.signer.to_account_info(),
+            },
+        ),
+        shares,
+    )?;
+    transfer_from_treasure(
+        &accounts.token_program,
+        &accounts.bank_token_account,
+        accounts.recipient_token_account.to_account_info(),
+        &accounts.mint,
+        ctx.bumps.bank_token_account,
+        amount,
+    )?;
+
+    msg!(
+        "Redeem completed: Signer: {}, Recipient: {}, Amount: {}, Receipts burned: {}",
+        accounts.signer.key(),
+        accounts.recipient_token_account.key(),
+        amount,
+        shares
+    );
+
+    let bank = &ctx.accounts.bank;
+    emit_cpi!(RedeemEvent {
+        signer:ctx.accounts.signer.key(),
+        mint:ctx.accounts.mint.key(),
+        recipient:ctx.accounts.recipient_token_account.key(),
+        amount,
+        shares,
+        total_deposits:bank.total_deposits,
+        total_deposit_shares:bank.total_deposit_shares,
+        timestamp:bank.last_updated,
+    });
+    Ok(())
+}
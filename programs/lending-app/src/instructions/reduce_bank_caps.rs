@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{error::ErrorCode, Bank, BankConfigChangedEvent, BankConfigUpdate};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReduceBankCaps<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = bank.authority == signer.key() || bank.guardian == signer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub bank:Account<'info,Bank>,
+    pub mint:InterfaceAccount<'info,Mint>,
+}
+
+// Lowering caps only reduces exposure, so the guardian can do it without the timelock.
+// Raising them goes through `update_bank_config`.
+pub fn process_reduce_bank_caps(ctx:Context<ReduceBankCaps>, deposit_cap:Option<u64>, borrow_cap:Option<u64>)->Result<()>{
+    let bank = &mut ctx.accounts.bank;
+
+    bank.reduce_caps(deposit_cap, borrow_cap)?;
+
+    msg!("Bank {} caps: deposit {}, borrow {}", bank.key(), bank.deposit_cap, bank.borrow_cap);
+    emit_cpi!(BankConfigChangedEvent {
+        mint:ctx.accounts.mint.key(),
+        signer:ctx.accounts.signer.key(),
+        applied:BankConfigUpdate { deposit_cap, borrow_cap, ..Default::default() },
+        pending:ctx.accounts.bank.pending_config,
+        pending_ready_at:ctx.accounts.bank.pending_config_ready_at,
+        timestamp:Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
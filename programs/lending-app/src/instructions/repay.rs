@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{token_2022::{transfer_checked, TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{accrued_interest, Bank, User};
+use crate::{accrue_bank_interest, book_repay, transfer_to_treasure, InterestAccruedEvent, RepayEvent, borrow_amount_to_shares, borrow_shares_to_amount, error::ErrorCode, Bank, Rounding, User};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Repay<'info> {
     #[account(mut)]
@@ -30,9 +31,11 @@ pub struct Repay<'info> {
         associated_token::token_program=token_program
     )]
     pub user_token_account:InterfaceAccount<'info,TokenAccount>,
+    // wallet whose debt is repaid, the signer pays for it and can be anyone
+    pub borrower:SystemAccount<'info>,
       #[account(
         mut,
-        seeds=[b"user",signer.key().as_ref()],
+        seeds=[b"user",borrower.key().as_ref(),&[user_account.index]],
         bump
     )]
     pub user_account:Account<'info,User>,
@@ -44,49 +47,61 @@ pub struct Repay<'info> {
 pub fn process_repay(ctx:Context<Repay>, amount:u64) -> Result<()> {
     let user_account = &mut ctx.accounts.user_account;
     let bank = &mut ctx.accounts.bank;
-    
-    let accrued_user_borrowed_amount:u64;
-    let initial_borrowed_amount:u64;
-    // Updating total borrowed in bank using euler's approach
-    if ctx.accounts.repay_mint.key() == user_account.mint_address.key() {      // it means user wants to pay USDC 
-        accrued_user_borrowed_amount =user_account.borrowed_usdc;
-        initial_borrowed_amount = user_account.borrowed_usdc
-    } else {
-        accrued_user_borrowed_amount = user_account.borrowed_sol;
-        initial_borrowed_amount = user_account.borrowed_sol;
+
+    // Updating the total borrowed amount with the interest accrued since the last update
+    let interest = accrue_bank_interest(bank)?;
+
+    let repay_key = ctx.accounts.repay_mint.key();
+    let user_borrowed_shares = user_account.borrow_shares(&repay_key);
+    if user_borrowed_shares == 0 {
+        return Err(ErrorCode::NoBorrowsFound.into());
     }
-    // Updating the total borrowed amount incrementing interest
-    let interest_accumulated = accrued_user_borrowed_amount.checked_sub(initial_borrowed_amount).unwrap();
-    bank.total_borrowed =  bank.total_borrowed.checked_add(interest_accumulated).unwrap();
+
+    // u64::MAX repays the whole debt: burn every borrowed share and charge what they are worth right now.
+    // Amounts owed round up and shares burned for a partial repayment round down.
+    let debt = borrow_shares_to_amount(bank, user_borrowed_shares, Rounding::Up)?;
+    let (amount, repay_amount_in_shares) = if amount == u64::MAX || amount == debt {
+        (debt, user_borrowed_shares)
+    } else if amount > debt {
+        return Err(ErrorCode::OverRepay.into());
+    } else {
+        (amount, borrow_amount_to_shares(bank, amount, Rounding::Down)?)
+    };
+
+    // updating the borrow amount in user's state and bank. The repaid amount includes interest, so it
+    // can exceed the recorded principal; a fully repaid position is cleared.
+    book_repay(bank, user_account, &repay_key, amount, repay_amount_in_shares)?;
+    bank.record_inflow(amount, Clock::get()?.unix_timestamp);
 
     // transferring the amount to bank from the user's token account
-    let cpi_context = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-         TransferChecked {
-            from: ctx.accounts.user_token_account.to_account_info(),
-            to: ctx.accounts.bank_token_account.to_account_info(),
-            authority:ctx.accounts.signer.to_account_info(),
-            mint:ctx.accounts.repay_mint.to_account_info()
-         }
-        );
-    transfer_checked(cpi_context, amount, ctx.accounts.repay_mint.decimals)?;
+    transfer_to_treasure(
+        &ctx.accounts.token_program,
+        ctx.accounts.user_token_account.to_account_info(),
+        &ctx.accounts.bank_token_account,
+        ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.repay_mint,
+        amount,
+    )?;
 
-    // calculating user shares and bank shares now-
-    let value_per_share = bank.total_borrowed.checked_div(bank.total_borrowed_shares).unwrap();
-    let repay_amount_in_shares = amount.checked_div(value_per_share).unwrap();
+    msg!(
+        "Repay completed: Payer: {}, Borrower: {}, Amount: {}, Shares burned: {}",
+        ctx.accounts.signer.key(),
+        ctx.accounts.borrower.key(),
+        amount,
+        repay_amount_in_shares
+    );
 
-    // updating the borrow amount in user's state and bank to reflect the transfer.
-    bank.total_borrowed_shares = bank.total_borrowed_shares.checked_sub(repay_amount_in_shares).unwrap();
-    bank.total_borrowed = bank.total_borrowed.checked_sub(amount).unwrap();
-    // Updating User's borrowed status
-    if ctx.accounts.repay_mint.key() == user_account.mint_address.key() { 
-        user_account.borrowed_usdc = user_account.borrowed_usdc.checked_sub(amount).unwrap();
-        user_account.borrowed_usdc_shares = user_account.borrowed_usdc_shares.checked_sub(repay_amount_in_shares).unwrap();
-    } else {
-        user_account.borrowed_sol = user_account.borrowed_sol.checked_sub(amount).unwrap();
-        user_account.borrowed_sol_shares = user_account.borrowed_sol_shares.checked_sub(repay_amount_in_shares).unwrap();
+    if interest > 0 {
+        emit_cpi!(InterestAccruedEvent::new(&ctx.accounts.bank, interest));
     }
+    emit_cpi!(RepayEvent::new(
+        &ctx.accounts.user_account,
+        ctx.accounts.signer.key(),
+        &ctx.accounts.bank,
+        amount,
+        repay_amount_in_shares,
+        ctx.accounts.bank.last_updated,
+    ));
 
     Ok(())
 }
-
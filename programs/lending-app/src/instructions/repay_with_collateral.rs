@@ -0,0 +1,182 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+    begin_swap, book_repay, book_withdraw, borrow_amount_to_shares, borrow_shares_to_amount,
+    check_health, deposit_amount_to_shares, error::ErrorCode, health_assets, load_health_accounts, swap_via_adapter,
+    transfer_from_treasure, transfer_to_treasure, Bank, HealthAsset, RepayEvent, Rounding, User, WithdrawEvent,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RepayWithCollateral<'info> {
+    #[account(mut)]
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"user",user_account.owner.as_ref(),&[user_account.index]],
+        bump,
+        constraint = user_account.is_owner_or_delegate(&signer.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub user_account:Box<Account<'info,User>>,
+    pub collateral_mint:Box<InterfaceAccount<'info,Mint>>,
+    #[account(constraint = debt_mint.key() != collateral_mint.key() @ ErrorCode::SameMint)]
+    pub debt_mint:Box<InterfaceAccount<'info,Mint>>,
+    #[account(
+        mut,
+        seeds=[b"bank",collateral_mint.key().as_ref()],
+        bump,
+        constraint = !collateral_bank.paused @ ErrorCode::BankPaused,
+    )]
+    pub collateral_bank:Box<Account<'info,Bank>>,
+    #[account(
+        mut,
+        seeds=[b"treasure",collateral_mint.key().as_ref()],
+        token::mint=collateral_mint,
+        token::authority=collateral_token_bank,
+        token::token_program = token_program,
+        bump
+    )]
+    pub collateral_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        seeds=[b"bank",debt_mint.key().as_ref()],
+        bump
+    )]
+    pub debt_bank:Box<Account<'info,Bank>>,
+    #[account(
+        mut,
+        seeds=[b"treasure",debt_mint.key().as_ref()],
+        token::mint=debt_mint,
+        token::authority=debt_token_bank,
+        token::token_program = token_program,
+        bump
+    )]
+    pub debt_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
+    // the withdrawn collateral lands here and is the input of the swap
+    #[account(
+        mut,
+        token::mint=collateral_mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_collateral_account:Box<InterfaceAccount<'info,TokenAccount>>,
+    // the swap output lands here and repays the debt, anything above the debt stays with the signer
+    #[account(
+        mut,
+        token::mint=debt_mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_debt_account:Box<InterfaceAccount<'info,TokenAccount>>,
+    // price accounts for the two mints, a Pyth price account holds a single feed
+    pub collateral_price_update:Box<Account<'info,PriceUpdateV2>>,
+    pub debt_price_update:Box<Account<'info,PriceUpdateV2>>,
+    /// CHECK: must be the collateral bank's configured swap program, checked in the handler
+    pub swap_program:UncheckedAccount<'info>,
+    pub token_program:Interface<'info,TokenInterface>
+    // remaining accounts: a (bank, price update) pair for each other bank the position uses,
+    // see load_health_accounts, then the accounts of the swap
+}
+
+// 1. Withdraw `collateral_amount` from the collateral bank to the signer.
+// 2. Swap it to the debt mint through the swap program, the accounts it needs follow the health accounts.
+// 3. Repay the debt with the swap output and check the position is still healthy.
+
+pub fn process_repay_with_collateral<'info>(
+    mut ctx:Context<'_, '_, 'info, 'info, RepayWithCollateral<'info>>,
+    collateral_amount:u64,
+    min_repay_amount:u64,
+    swap_data:Vec<u8>,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let interest_events = begin_swap(
+        &accounts.user_account,
+        &accounts.signer.key(),
+        &accounts.swap_program.key(),
+        &mut accounts.collateral_bank,
+        &mut accounts.debt_bank,
+    )?;
+
+    let collateral_key = accounts.collateral_mint.key();
+    let debt_key = accounts.debt_mint.key();
+    let now = Clock::get()?.unix_timestamp;
+    let (others, adapter_accounts) = load_health_accounts(&accounts.user_account, &[collateral_key, debt_key], ctx.remaining_accounts)?;
+
+    if accounts.user_account.borrow_shares(&debt_key) == 0 {
+        return Err(ErrorCode::NoBorrowsFound.into());
+    }
+
+    // Withdraw the collateral, shares burned round up in favour of the bank
+    let collateral_shares = deposit_amount_to_shares(&accounts.collateral_bank, collateral_amount, Rounding::Up)?;
+    book_withdraw(&mut accounts.collateral_bank, &mut accounts.user_account, &collateral_key, collateral_amount, collateral_shares)?;
+    accounts.collateral_bank.record_outflow(collateral_amount, now)?;
+    transfer_from_treasure(
+        &accounts.token_program,
+        &accounts.collateral_token_bank,
+        accounts.signer_collateral_account.to_account_info(),
+        &accounts.collateral_mint,
+        ctx.bumps.collateral_token_bank,
+        collateral_amount,
+    )?;
+
+    let swapped = swap_via_adapter(
+        &accounts.swap_program.to_account_info(),
+        adapter_accounts,
+        &swap_data,
+        collateral_amount,
+        min_repay_amount,
+        &mut accounts.signer_debt_account,
+    )?;
+
+    // Repay at most the whole debt, the rest of the swap output stays with the signer
+    let debt_shares = accounts.user_account.borrow_shares(&debt_key);
+    let debt = borrow_shares_to_amount(&accounts.debt_bank, debt_shares, Rounding::Up)?;
+    let (repay_amount, repay_shares) = if swapped >= debt {
+        (debt, debt_shares)
+    } else {
+        (swapped, borrow_amount_to_shares(&accounts.debt_bank, swapped, Rounding::Down)?)
+    };
+    transfer_to_treasure(
+        &accounts.token_program,
+        accounts.signer_debt_account.to_account_info(),
+        &accounts.debt_token_bank,
+        accounts.signer.to_account_info(),
+        &accounts.debt_mint,
+        repay_amount,
+    )?;
+    book_repay(&mut accounts.debt_bank, &mut accounts.user_account, &debt_key, repay_amount, repay_shares)?;
+    accounts.debt_bank.record_inflow(repay_amount, now);
+
+    check_health(
+        &accounts.user_account,
+        &health_assets(
+            &[
+                HealthAsset { bank: &accounts.collateral_bank, price_update: &accounts.collateral_price_update },
+                HealthAsset { bank: &accounts.debt_bank, price_update: &accounts.debt_price_update },
+            ],
+            &others,
+        ),
+    )?;
+
+    msg!(
+        "Repay with collateral completed: User: {}, Collateral withdrawn: {}, Swapped: {}, Repaid: {}, Shares burned: {}",
+        accounts.user_account.key(),
+        collateral_amount,
+        swapped,
+        repay_amount,
+        repay_shares
+    );
+
+    let signer = accounts.signer.key();
+    let withdraw_event = WithdrawEvent::new(&accounts.user_account, signer, &accounts.collateral_bank, collateral_amount, collateral_shares, now);
+    let repay_event = RepayEvent::new(&accounts.user_account, signer, &accounts.debt_bank, repay_amount, repay_shares, now);
+    for event in interest_events.into_iter().filter(|event| event.interest > 0) {
+        emit_cpi!(event);
+    }
+    emit_cpi!(withdraw_event);
+    emit_cpi!(repay_event);
+
+    Ok(())
+}
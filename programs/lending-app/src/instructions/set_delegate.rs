@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{DelegateSetEvent, User};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"user",signer.key().as_ref(),&[user_account.index]],
+        bump
+    )]
+    pub user_account:Account<'info,User>,
+}
+
+// Set `delegate` to Pubkey::default() to revoke it.
+pub fn process_set_delegate(ctx:Context<SetDelegate>, delegate:Pubkey, delegate_can_receive:bool)->Result<()>{
+    let user = &mut ctx.accounts.user_account;
+    user.delegate = delegate;
+    user.delegate_can_receive = delegate_can_receive && delegate != Pubkey::default();
+    msg!("Delegate of {} set to {} (can receive: {})", user.owner, delegate, user.delegate_can_receive);
+    emit_cpi!(DelegateSetEvent {
+        user:user.key(),
+        owner:user.owner,
+        delegate,
+        delegate_can_receive:user.delegate_can_receive,
+        timestamp:Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{error::ErrorCode, Bank, GuardianSetEvent};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = bank.authority == signer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub bank:Account<'info,Bank>,
+    pub mint:InterfaceAccount<'info,Mint>,
+}
+
+// Only the bank authority can hand out (or rotate) the guardian key.
+pub fn process_set_guardian(ctx:Context<SetGuardian>, guardian:Pubkey)->Result<()>{
+    let bank = &mut ctx.accounts.bank;
+    bank.guardian = guardian;
+    msg!("Guardian of bank {} set to {}", bank.key(), guardian);
+    emit_cpi!(GuardianSetEvent {
+        mint:ctx.accounts.mint.key(),
+        signer:ctx.accounts.signer.key(),
+        guardian,
+        timestamp:Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
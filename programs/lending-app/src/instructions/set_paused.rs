@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{error::ErrorCode, Bank, BankPausedEvent};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = bank.authority == signer.key() || bank.guardian == signer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub bank:Account<'info,Bank>,
+    pub mint:InterfaceAccount<'info,Mint>,
+}
+
+// The guardian is allowed to pause the bank in an emergency,
+// but only the authority can unpause it again.
+pub fn process_set_paused(ctx:Context<SetPaused>, paused:bool)->Result<()>{
+    let bank = &mut ctx.accounts.bank;
+    bank.set_paused(&ctx.accounts.signer.key(), paused)?;
+    msg!("Bank {} paused: {}", bank.key(), paused);
+    emit_cpi!(BankPausedEvent {
+        mint:ctx.accounts.mint.key(),
+        signer:ctx.accounts.signer.key(),
+        paused,
+        timestamp:Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
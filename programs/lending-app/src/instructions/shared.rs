@@ -1,7 +1,9 @@
-use std::f64::consts::E;
-use pyth_solana_receiver_sdk::price_update::Price;
 use crate::error::ErrorCode;
+use crate::{Bank, User, MAX_AGE};
 use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{mint_to, transfer_checked, MintTo, TransferChecked};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::{Price, PriceUpdateV2};
 
 pub fn accrued_interest(principle_amount: u64, roi: u64, last_updated: i64) -> Result<u64> {
     let clock = Clock::get()?;
@@ -33,6 +35,79 @@ let interest = principle_amount
         .ok_or(ErrorCode::MathOverflow)?)
 }
 
+/// Brings `total_borrowed` up to date with the interest accrued since `last_updated`.
+/// The interest is owed to suppliers, so `total_deposits` grows by the same amount.
+/// Returns the interest accrued.
+pub fn accrue_bank_interest(bank: &mut Bank) -> Result<u64> {
+    let clock = Clock::get()?;
+    let mut interest = 0;
+
+    if bank.total_borrowed > 0 {
+        let new_total_borrowed = accrued_interest(bank.total_borrowed, bank.interest_rate, bank.last_updated)?;
+        interest = new_total_borrowed
+            .checked_sub(bank.total_borrowed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        bank.total_borrowed = new_total_borrowed;
+        bank.total_deposits = bank
+            .total_deposits
+            .checked_add(interest)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    bank.last_updated = clock.unix_timestamp;
+    Ok(interest)
+}
+
+/// Direction to round a share conversion in. Every handler rounds in favor of the protocol:
+/// shares minted and amounts paid out round down, shares burned and amounts owed round up.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// `value * numerator / denominator` with a u128 intermediate.
+pub fn mul_div(value: u64, numerator: u128, denominator: u128, rounding: Rounding) -> Result<u64> {
+    let product = (value as u128)
+        .checked_mul(numerator)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let quotient = product
+        .checked_div(denominator)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let quotient = if rounding == Rounding::Up && product % denominator != 0 {
+        quotient + 1
+    } else {
+        quotient
+    };
+    u64::try_from(quotient).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// Deposit shares worth `amount`, priced including the virtual offsets.
+pub fn deposit_amount_to_shares(bank: &Bank, amount: u64, rounding: Rounding) -> Result<u64> {
+    mul_div(amount, bank.virtual_deposit_shares(), bank.virtual_total_deposits(), rounding)
+}
+
+/// Amount that `shares` deposit shares are worth, priced including the virtual offsets.
+pub fn deposit_shares_to_amount(bank: &Bank, shares: u64, rounding: Rounding) -> Result<u64> {
+    mul_div(shares, bank.virtual_total_deposits(), bank.virtual_deposit_shares(), rounding)
+}
+
+/// Debt shares worth `amount`. An empty bank maps debt 1:1.
+pub fn borrow_amount_to_shares(bank: &Bank, amount: u64, rounding: Rounding) -> Result<u64> {
+    if bank.total_borrowed == 0 || bank.total_borrowed_shares == 0 {
+        return Ok(amount);
+    }
+    mul_div(amount, bank.total_borrowed_shares as u128, bank.total_borrowed as u128, rounding)
+}
+
+/// Debt that `shares` debt shares are worth.
+pub fn borrow_shares_to_amount(bank: &Bank, shares: u64, rounding: Rounding) -> Result<u64> {
+    if bank.total_borrowed_shares == 0 {
+        return Ok(shares);
+    }
+    mul_div(shares, bank.total_borrowed as u128, bank.total_borrowed_shares as u128, rounding)
+}
+
 /// Normalize a Pyth price into a fixed-point u128 with 6 decimals (like "micro USD").
 /// Example:
 ///   price = 42120000, expo = -6  =>  42_120_000 (42.12 USD in micro-units)
@@ -62,3 +137,372 @@ pub fn normalize_pyth_price(price: i64, expo: i32) -> Result<u128> {
             .map_err(|_| ErrorCode::MathOverflow.into())
     }
 }
+
+/// Books a supply of `amount` for `shares` on the bank alone, checking the first deposit and the cap.
+pub fn book_supply(bank: &mut Bank, amount: u64, shares: u64) -> Result<()> {
+    if shares == 0 {
+        return Err(ErrorCode::ZeroShares.into());
+    }
+    if bank.total_deposit_shares == 0 && amount < bank.min_first_deposit {
+        return Err(ErrorCode::FirstDepositTooSmall.into());
+    }
+    let total_deposits = bank.total_deposits.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    if total_deposits > bank.deposit_cap {
+        return Err(ErrorCode::DepositCapExceeded.into());
+    }
+
+    bank.total_deposits = total_deposits;
+    bank.total_deposit_shares = bank
+        .total_deposit_shares
+        .checked_add(shares)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Books a deposit of `amount` for `shares` on the bank and the user's position.
+pub fn book_deposit(bank: &mut Bank, user: &mut User, mint: &Pubkey, amount: u64, shares: u64) -> Result<()> {
+    book_supply(bank, amount, shares)?;
+
+    let (deposited, deposited_shares) = user.deposit_position(mint)?;
+    *deposited = deposited.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    *deposited_shares = deposited_shares.checked_add(shares).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Books a withdrawal of `amount` for `shares` on the bank and the user's position.
+pub fn book_withdraw(bank: &mut Bank, user: &mut User, mint: &Pubkey, amount: u64, shares: u64) -> Result<()> {
+    let (deposited, deposited_shares) = user.deposit_position(mint)?;
+    if shares > *deposited_shares {
+        return Err(ErrorCode::InsufficientShares.into());
+    }
+    *deposited_shares -= shares;
+    *deposited = if *deposited_shares == 0 { 0 } else { deposited.saturating_sub(amount) };
+    user.release_position(mint);
+    book_redeem(bank, amount, shares)
+}
+
+/// Books a redemption of `shares` for `amount` on the bank alone.
+pub fn book_redeem(bank: &mut Bank, amount: u64, shares: u64) -> Result<()> {
+    bank.total_deposits = bank.total_deposits.checked_sub(amount).ok_or(ErrorCode::MathError)?;
+    bank.total_deposit_shares = bank
+        .total_deposit_shares
+        .checked_sub(shares)
+        .ok_or(ErrorCode::MathError)?;
+    Ok(())
+}
+
+/// Books a new borrow of `amount`, adding the origination fee to the debt.
+/// Returns the origination fee and the debt shares minted.
+pub fn book_borrow(bank: &mut Bank, user: &mut User, mint: &Pubkey, amount: u64) -> Result<(u64, u64)> {
+    let origination_fee = mul_div(amount, bank.origination_fee as u128, 10_000, Rounding::Up)?;
+    let debt_amount = amount.checked_add(origination_fee).ok_or(ErrorCode::MathOverflow)?;
+    let total_borrowed = bank.total_borrowed.checked_add(debt_amount).ok_or(ErrorCode::MathOverflow)?;
+    if total_borrowed > bank.borrow_cap {
+        return Err(ErrorCode::BorrowCapExceeded.into());
+    }
+    let shares = borrow_amount_to_shares(bank, debt_amount, Rounding::Up)?;
+
+    let (borrowed, borrowed_shares) = user.borrow_position(mint)?;
+    *borrowed = borrowed.checked_add(debt_amount).ok_or(ErrorCode::MathOverflow)?;
+    *borrowed_shares = borrowed_shares.checked_add(shares).ok_or(ErrorCode::MathOverflow)?;
+
+    bank.total_borrowed = total_borrowed;
+    bank.total_borrowed_shares = bank
+        .total_borrowed_shares
+        .checked_add(shares)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let protocol_fee = mul_div(origination_fee, bank.origination_fee_protocol_share as u128, 10_000, Rounding::Down)?;
+    bank.protocol_fees = bank.protocol_fees.checked_add(protocol_fee).ok_or(ErrorCode::MathOverflow)?;
+    bank.total_deposits = bank
+        .total_deposits
+        .checked_add(origination_fee - protocol_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok((origination_fee, shares))
+}
+
+/// Books a repayment of `amount` for `shares` on the bank and the user's position.
+pub fn book_repay(bank: &mut Bank, user: &mut User, mint: &Pubkey, amount: u64, shares: u64) -> Result<()> {
+    let (borrowed, borrowed_shares) = user.borrow_position(mint)?;
+    if shares > *borrowed_shares {
+        return Err(ErrorCode::InsufficientShares.into());
+    }
+    *borrowed_shares -= shares;
+    *borrowed = if *borrowed_shares == 0 { 0 } else { borrowed.saturating_sub(amount) };
+    user.release_position(mint);
+
+    bank.total_borrowed = bank.total_borrowed.checked_sub(amount).ok_or(ErrorCode::MathError)?;
+    bank.total_borrowed_shares = bank
+        .total_borrowed_shares
+        .checked_sub(shares)
+        .ok_or(ErrorCode::MathError)?;
+    Ok(())
+}
+
+/// Sends `amount` out of a bank's treasure vault, signed by the vault PDA.
+pub fn transfer_from_treasure<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    treasure: &InterfaceAccount<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    treasure_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let mint_key = mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[b"treasure", mint_key.as_ref(), &[treasure_bump]]];
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: treasure.to_account_info(),
+            to,
+            authority: treasure.to_account_info(),
+            mint: mint.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer_checked(cpi_context, amount, mint.decimals)
+}
+
+/// Moves `amount` from a token account owned by `authority` into a bank's treasure vault.
+pub fn transfer_to_treasure<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: AccountInfo<'info>,
+    treasure: &InterfaceAccount<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_context = CpiContext::new(
+        token_program.to_account_info(),
+        TransferChecked {
+            from,
+            to: treasure.to_account_info(),
+            authority,
+            mint: mint.to_account_info(),
+        },
+    );
+    transfer_checked(cpi_context, amount, mint.decimals)
+}
+
+/// Mints deposit receipts, signed by the bank PDA of `mint_key`.
+pub fn mint_receipts<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    receipt_mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    bank: AccountInfo<'info>,
+    mint_key: &Pubkey,
+    bank_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[b"bank", mint_key.as_ref(), &[bank_bump]]];
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        MintTo {
+            mint: receipt_mint.to_account_info(),
+            to,
+            authority: bank,
+        },
+        signer_seeds,
+    );
+    mint_to(cpi_context, amount)
+}
+
+/// Value of `amount` base units in micro-USD, keeping the full precision of the Pyth price.
+pub fn token_value(amount: u64, price: &Price, decimals: u8) -> Result<u128> {
+    let unit_price = u128::try_from(price.price).map_err(|_| ErrorCode::MathOverflow)?;
+    let value = (amount as u128)
+        .checked_mul(unit_price)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let exponent = 6 + price.exponent - decimals as i32;
+    let scale = 10_u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(ErrorCode::MathOverflow)?;
+    if exponent >= 0 {
+        Ok(value.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?)
+    } else {
+        Ok(value / scale)
+    }
+}
+
+/// Number of base units worth `value` micro-USD, the inverse of `token_value`, rounded down.
+pub fn token_amount(value: u128, price: &Price, decimals: u8) -> Result<u64> {
+    let unit_price = u128::try_from(price.price).map_err(|_| ErrorCode::MathOverflow)?;
+    let exponent = 6 + price.exponent - decimals as i32;
+    let scale = 10_u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(ErrorCode::MathOverflow)?;
+    let amount = if exponent >= 0 {
+        value.checked_div(unit_price.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?)
+    } else {
+        value.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?.checked_div(unit_price)
+    };
+    let amount = amount.ok_or(ErrorCode::MathOverflow)?;
+    Ok(u64::try_from(amount).map_err(|_| ErrorCode::MathOverflow)?)
+}
+
+/// Fresh price of the bank's asset from its own Pyth feed.
+pub fn asset_price(bank: &Bank, price_update: &PriceUpdateV2) -> Result<Price> {
+    Ok(price_update.get_price_no_older_than(&Clock::get()?, MAX_AGE, &bank.price_feed_id)?)
+}
+
+/// A bank a position may hold deposits or debt in, for health checks.
+/// A Pyth price account holds a single feed, so every asset comes with its own.
+#[derive(Clone, Copy)]
+pub struct HealthAsset<'a> {
+    pub bank: &'a Bank,
+    pub price_update: &'a PriceUpdateV2,
+}
+
+/// Bank and price of a position's bank the instruction doesn't otherwise load, read from its
+/// remaining accounts. The bank is a copy, interest accrued on it is not written back.
+pub struct HealthAccount {
+    pub bank: Bank,
+    pub price_update: PriceUpdateV2,
+}
+
+impl HealthAccount {
+    pub fn asset(&self) -> HealthAsset<'_> {
+        HealthAsset { bank: &self.bank, price_update: &self.price_update }
+    }
+}
+
+/// Reads a `(bank, price update)` pair from the front of `remaining_accounts` for every bank the
+/// position uses besides the banks of `loaded`, in the order of the position's slots.
+/// Returns them with the accounts left over, which the swap instructions pass to the adapter.
+pub fn load_health_accounts<'a, 'info>(
+    user: &User,
+    loaded: &[Pubkey],
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<(Vec<HealthAccount>, &'a [AccountInfo<'info>])> {
+    let mints: Vec<Pubkey> = user
+        .open_positions()
+        .map(|position| position.mint)
+        .filter(|mint| !loaded.contains(mint))
+        .collect();
+    if remaining_accounts.len() < mints.len() * 2 {
+        return Err(ErrorCode::MissingHealthAccount.into());
+    }
+    let (pairs, rest) = remaining_accounts.split_at(mints.len() * 2);
+
+    let mut accounts = Vec::with_capacity(mints.len());
+    for (mint, pair) in mints.iter().zip(pairs.chunks(2)) {
+        let mut bank: Bank = load_account(&pair[0])?;
+        if bank.mint_address != *mint {
+            return Err(ErrorCode::InvalidHealthAccount.into());
+        }
+        accrue_bank_interest(&mut bank)?;
+        accounts.push(HealthAccount { bank, price_update: load_account(&pair[1])? });
+    }
+    Ok((accounts, rest))
+}
+
+/// The instruction's own `assets` followed by the position's other banks.
+pub fn health_assets<'a>(assets: &[HealthAsset<'a>], others: &'a [HealthAccount]) -> Vec<HealthAsset<'a>> {
+    assets.iter().copied().chain(others.iter().map(HealthAccount::asset)).collect()
+}
+
+fn load_account<T: AccountDeserialize + Owner>(info: &AccountInfo) -> Result<T> {
+    if *info.owner != T::owner() {
+        return Err(ErrorCode::InvalidHealthAccount.into());
+    }
+    T::try_deserialize(&mut &info.try_borrow_data()?[..])
+}
+
+/// Value of a position in micro-USD.
+pub struct PositionValue {
+    pub collateral: u128,
+    // collateral weighted by each bank's max_ltv
+    pub borrowable: u128,
+    // collateral weighted by each bank's liquidation_threshold
+    pub liquidation_limit: u128,
+    pub debt: u128,
+}
+
+/// Values every bank the position uses, `assets` has to cover all of them.
+pub fn position_value(user: &User, assets: &[HealthAsset]) -> Result<PositionValue> {
+    let mut value = PositionValue { collateral: 0, borrowable: 0, liquidation_limit: 0, debt: 0 };
+
+    for position in user.open_positions() {
+        let asset = assets
+            .iter()
+            .find(|asset| asset.bank.mint_address == position.mint)
+            .ok_or(ErrorCode::MissingHealthAccount)?;
+        let price = asset_price(asset.bank, asset.price_update)?;
+
+        let deposited = deposit_shares_to_amount(asset.bank, position.deposited_shares, Rounding::Down)?;
+        let borrowed = borrow_shares_to_amount(asset.bank, position.borrowed_shares, Rounding::Up)?;
+        let collateral = token_value(deposited, &price, asset.bank.decimals)?;
+        let debt = token_value(borrowed, &price, asset.bank.decimals)?;
+
+        value.collateral = value.collateral.checked_add(collateral).ok_or(ErrorCode::MathOverflow)?;
+        value.borrowable = value
+            .borrowable
+            .checked_add(collateral * asset.bank.max_ltv as u128 / 10_000)
+            .ok_or(ErrorCode::MathOverflow)?;
+        value.liquidation_limit = value
+            .liquidation_limit
+            .checked_add(collateral * asset.bank.liquidation_threshold as u128 / 10_000)
+            .ok_or(ErrorCode::MathOverflow)?;
+        value.debt = value.debt.checked_add(debt).ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    Ok(value)
+}
+
+/// Fails if the position's debt is above what its collateral allows under max_ltv.
+pub fn check_health(user: &User, assets: &[HealthAsset]) -> Result<()> {
+    let value = position_value(user, assets)?;
+    if value.debt > value.borrowable {
+        return Err(ErrorCode::PositionUnhealthy.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::tests::{empty_user, test_bank};
+
+    fn fee_bank(origination_fee: u64, protocol_share: u64) -> Bank {
+        Bank {
+            origination_fee,
+            origination_fee_protocol_share: protocol_share,
+            total_deposits: 1_000_000,
+            total_deposit_shares: 1_000_000,
+            ..test_bank()
+        }
+    }
+
+    #[test]
+    fn origination_fee_is_added_to_the_debt() {
+        let (mut bank, mut user, mint) = (fee_bank(100, 2_000), empty_user(), Pubkey::new_unique());
+        let (fee, shares) = book_borrow(&mut bank, &mut user, &mint, 10_000).unwrap();
+
+        assert_eq!(fee, 100);
+        assert_eq!(shares, 10_100);
+        assert_eq!(user.borrowed(&mint), 10_100);
+        assert_eq!(bank.total_borrowed, 10_100);
+        // 20% of the fee goes to the protocol, the rest to the depositors
+        assert_eq!(bank.protocol_fees, 20);
+        assert_eq!(bank.total_deposits, 1_000_080);
+    }
+
+    #[test]
+    fn origination_fee_rounds_up() {
+        let (mut bank, mut user, mint) = (fee_bank(100, 10_000), empty_user(), Pubkey::new_unique());
+        let (fee, _) = book_borrow(&mut bank, &mut user, &mint, 1).unwrap();
+
+        assert_eq!(fee, 1);
+        assert_eq!(user.borrowed(&mint), 2);
+        assert_eq!(bank.protocol_fees, 1);
+        assert_eq!(bank.total_deposits, 1_000_000);
+    }
+
+    #[test]
+    fn origination_fee_counts_against_the_borrow_cap() {
+        let mut bank = Bank { borrow_cap: 10_050, ..fee_bank(100, 0) };
+        let (mut user, mint) = (empty_user(), Pubkey::new_unique());
+        assert!(book_borrow(&mut bank, &mut user, &mint, 10_000).is_err());
+        assert_eq!(bank.total_borrowed, 0);
+        assert!(user.position(&mint).is_none());
+    }
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    accrue_bank_interest, book_supply, deposit_amount_to_shares, error::ErrorCode, mint_receipts, transfer_to_treasure,
+    Bank, InterestAccruedEvent, Rounding, SupplyEvent,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Supply<'info> {
+    #[account(mut)]
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = !bank.paused @ ErrorCode::BankPaused,
+    )]
+    pub bank:Account<'info,Bank>,
+    #[account(
+        mut,
+        seeds=[b"treasure",mint.key().as_ref()],
+        token::mint=mint,
+        token::authority=bank_token_account,
+        token::token_program=token_program,
+        bump
+    )]
+    pub bank_token_account:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        token::mint=mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_token_account:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        seeds=[b"receipt",mint.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint:InterfaceAccount<'info,Mint>,
+    // any receipt account, e.g. one owned by a vault strategy
+    #[account(
+        mut,
+        token::mint=receipt_mint,
+        token::token_program=token_program,
+    )]
+    pub receipt_account:InterfaceAccount<'info,TokenAccount>,
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+// Supplies `amount` to the bank without opening a position: the shares are minted as receipt
+// tokens, one per share, and follow the tokens wherever they are transferred.
+// Receipts earn interest like any deposit but can't back a borrow.
+pub fn process_supply(mut ctx:Context<Supply>, amount:u64)->Result<()>{
+    let interest = accrue_bank_interest(&mut ctx.accounts.bank)?;
+    if interest > 0 {
+        emit_cpi!(InterestAccruedEvent::new(&ctx.accounts.bank, interest));
+    }
+    let accounts = &mut ctx.accounts;
+    let mint_key = accounts.mint.key();
+
+    // Shares minted round down, like a deposit.
+    let shares = deposit_amount_to_shares(&accounts.bank, amount, Rounding::Down)?;
+    book_supply(&mut accounts.bank, amount, shares)?;
+    accounts.bank.record_inflow(amount, Clock::get()?.unix_timestamp);
+
+    transfer_to_treasure(
+        &accounts.token_program,
+        accounts.signer_token_account.to_account_info(),
+        &accounts.bank_token_account,
+        accounts.signer.to_account_info(),
+        &accounts.mint,
+        amount,
+    )?;
+    mint_receipts(
+        &accounts.token_program,
+        &accounts.receipt_mint,
+        accounts.receipt_account.to_account_info(),
+        accounts.bank.to_account_info(),
+        &mint_key,
+        ctx.bumps.bank,
+        shares,
+    )?;
+
+    msg!(
+        "Supply completed: Signer: {}, Receipt account: {}, Amount: {}, Receipts minted: {}",
+        accounts.signer.key(),
+        accounts.receipt_account.key(),
+        amount,
+        shares
+    );
+
+    let bank = &ctx.accounts.bank;
+    emit_cpi!(SupplyEvent {
+        signer:ctx.accounts.signer.key(),
+        mint:mint_key,
+        receipt_account:ctx.accounts.receipt_account.key(),
+        amount,
+        shares,
+        total_deposits:bank.total_deposits,
+        total_deposit_shares:bank.total_deposit_shares,
+        timestamp:bank.last_updated,
+    });
+    Ok(())
+}
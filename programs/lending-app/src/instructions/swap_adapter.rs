@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::{AccountMeta, Instruction}, program::invoke};
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{accrue_bank_interest, error::ErrorCode, Bank, InterestAccruedEvent, User};
+
+/// Checks shared by the swap instructions, then accrues interest on both banks they touch.
+///
+/// `swap_bank` is the bank whose swap program is used. Tokens pass through the signer's own
+/// accounts, so a delegate needs permission to receive funds. Returns the interest events to
+/// emit with the instruction's own events.
+pub fn begin_swap(
+    user: &User,
+    signer: &Pubkey,
+    swap_program: &Pubkey,
+    swap_bank: &mut Bank,
+    other_bank: &mut Bank,
+) -> Result<[InterestAccruedEvent; 2]> {
+    swap_bank.check_swap_program(swap_program)?;
+    user.check_recipient(signer, signer)?;
+
+    let swap_interest = accrue_bank_interest(swap_bank)?;
+    let other_interest = accrue_bank_interest(other_bank)?;
+    Ok([
+        InterestAccruedEvent::new(swap_bank, swap_interest),
+        InterestAccruedEvent::new(other_bank, other_interest),
+    ])
+}
+
+/// Swaps through a bank's configured swap program.
+///
+/// The adapter is called with `swap_data` followed by `amount_in` and `min_amount_out`
+/// (both u64 little endian) and receives the remaining accounts after the health accounts
+/// unchanged, so the signer can authorize the transfer of its input tokens. Returns the
+/// amount that arrived in `destination`.
+pub fn swap_via_adapter<'info>(
+    swap_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    swap_data: &[u8],
+    amount_in: u64,
+    min_amount_out: u64,
+    destination: &mut InterfaceAccount<'info, TokenAccount>,
+) -> Result<u64> {
+    let balance_before = destination.amount;
+
+    let mut data = Vec::with_capacity(swap_data.len() + 16);
+    data.extend_from_slice(swap_data);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    let accounts = remaining_accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: account.key(),
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        })
+        .collect();
+    let ix = Instruction {
+        program_id: swap_program.key(),
+        accounts,
+        data,
+    };
+
+    let mut account_infos = remaining_accounts.to_vec();
+    account_infos.push(swap_program.clone());
+    invoke(&ix, &account_infos)?;
+
+    destination.reload()?;
+    let amount_out = destination
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(ErrorCode::SwapOutputTooLow)?;
+    if amount_out < min_amount_out {
+        return Err(ErrorCode::SwapOutputTooLow.into());
+    }
+    Ok(amount_out)
+}
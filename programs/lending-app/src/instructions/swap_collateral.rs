@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+    begin_swap, book_deposit, book_withdraw, check_health, deposit_amount_to_shares, error::ErrorCode,
+    health_assets, load_health_accounts, swap_via_adapter, transfer_from_treasure, transfer_to_treasure, Bank,
+    DepositEvent, HealthAsset, Rounding, User, WithdrawEvent,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapCollateral<'info> {
+    #[account(mut)]
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"user",user_account.owner.as_ref(),&[user_account.index]],
+        bump,
+        constraint = user_account.is_owner_or_delegate(&signer.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub user_account:Box<Account<'info,User>>,
+    pub from_mint:Box<InterfaceAccount<'info,Mint>>,
+    #[account(constraint = to_mint.key() != from_mint.key() @ ErrorCode::SameMint)]
+    pub to_mint:Box<InterfaceAccount<'info,Mint>>,
+    #[account(
+        mut,
+        seeds=[b"bank",from_mint.key().as_ref()],
+        bump,
+        constraint = !from_bank.paused @ ErrorCode::BankPaused,
+    )]
+    pub from_bank:Box<Account<'info,Bank>>,
+    #[account(
+        mut,
+        seeds=[b"treasure",from_mint.key().as_ref()],
+        token::mint=from_mint,
+        token::authority=from_token_bank,
+        token::token_program = token_program,
+        bump
+    )]
+    pub from_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        seeds=[b"bank",to_mint.key().as_ref()],
+        bump,
+        constraint = !to_bank.paused @ ErrorCode::BankPaused,
+    )]
+    pub to_bank:Box<Account<'info,Bank>>,
+    #[account(
+        mut,
+        seeds=[b"treasure",to_mint.key().as_ref()],
+        token::mint=to_mint,
+        token::authority=to_token_bank,
+        token::token_program = token_program,
+        bump
+    )]
+    pub to_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
+    // the withdrawn collateral lands here and is the input of the swap
+    #[account(
+        mut,
+        token::mint=from_mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_from_account:Box<InterfaceAccount<'info,TokenAccount>>,
+    // the swap output lands here and is deposited into the other bank
+    #[account(
+        mut,
+        token::mint=to_mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_to_account:Box<InterfaceAccount<'info,TokenAccount>>,
+    // price accounts for the two mints, a Pyth price account holds a single feed
+    pub from_price_update:Box<Account<'info,PriceUpdateV2>>,
+    pub to_price_update:Box<Account<'info,PriceUpdateV2>>,
+    /// CHECK: must be the source bank's configured swap program, checked in the handler
+    pub swap_program:UncheckedAccount<'info>,
+    pub token_program:Interface<'info,TokenInterface>
+    // remaining accounts: a (bank, price update) pair for each other bank the position uses,
+    // see load_health_accounts, then the accounts of the swap
+}
+
+// 1. Withdraw `amount` from the source bank to the signer.
+// 2. Swap it to the other mint through the swap program.
+// 3. Deposit the whole output into the destination bank.
+// Borrows stay open, the position only has to be healthy once the collateral has moved.
+
+pub fn process_swap_collateral<'info>(
+    mut ctx:Context<'_, '_, 'info, 'info, SwapCollateral<'info>>,
+    amount:u64,
+    min_amount_out:u64,
+    swap_data:Vec<u8>,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let interest_events = begin_swap(
+        &accounts.user_account,
+        &accounts.signer.key(),
+        &accounts.swap_program.key(),
+        &mut accounts.from_bank,
+        &mut accounts.to_bank,
+    )?;
+
+    let from_key = accounts.from_mint.key();
+    let to_key = accounts.to_mint.key();
+    let now = Clock::get()?.unix_timestamp;
+    let (others, adapter_accounts) = load_health_accounts(&accounts.user_account, &[from_key, to_key], ctx.remaining_accounts)?;
+
+    // Withdraw, shares burned round up in favour of the bank
+    let from_shares = deposit_amount_to_shares(&accounts.from_bank, amount, Rounding::Up)?;
+    book_withdraw(&mut accounts.from_bank, &mut accounts.user_account, &from_key, amount, from_shares)?;
+    accounts.from_bank.record_outflow(amount, now)?;
+    transfer_from_treasure(
+        &accounts.token_program,
+        &accounts.from_token_bank,
+        accounts.signer_from_account.to_account_info(),
+        &accounts.from_mint,
+        ctx.bumps.from_token_bank,
+        amount,
+    )?;
+
+    let swapped = swap_via_adapter(
+        &accounts.swap_program.to_account_info(),
+        adapter_accounts,
+        &swap_data,
+        amount,
+        min_amount_out,
+        &mut accounts.signer_to_account,
+    )?;
+
+    // Deposit, shares minted round down in favour of the bank
+    let to_shares = deposit_amount_to_shares(&accounts.to_bank, swapped, Rounding::Down)?;
+    book_deposit(&mut accounts.to_bank, &mut accounts.user_account, &to_key, swapped, to_shares)?;
+    transfer_to_treasure(
+        &accounts.token_program,
+        accounts.signer_to_account.to_account_info(),
+        &accounts.to_token_bank,
+        accounts.signer.to_account_info(),
+        &accounts.to_mint,
+        swapped,
+    )?;
+    accounts.to_bank.record_inflow(swapped, now);
+
+    check_health(
+        &accounts.user_account,
+        &health_assets(
+            &[
+                HealthAsset { bank: &accounts.from_bank, price_update: &accounts.from_price_update },
+                HealthAsset { bank: &accounts.to_bank, price_update: &accounts.to_price_update },
+            ],
+            &others,
+        ),
+    )?;
+
+    msg!(
+        "Collateral swap completed: User: {}, Withdrawn: {} of {}, Deposited: {} of {}",
+        accounts.user_account.key(),
+        amount,
+        from_key,
+        swapped,
+        to_key
+    );
+
+    let signer = accounts.signer.key();
+    let withdraw_event = WithdrawEvent::new(&accounts.user_account, signer, &accounts.from_bank, amount, from_shares, now);
+    let deposit_event = DepositEvent::new(&accounts.user_account, signer, &accounts.to_bank, swapped, to_shares, now);
+    for event in interest_events.into_iter().filter(|event| event.interest > 0) {
+        emit_cpi!(event);
+    }
+    emit_cpi!(withdraw_event);
+    emit_cpi!(deposit_event);
+
+    Ok(())
+}
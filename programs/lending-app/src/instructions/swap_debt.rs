@@ -0,0 +1,187 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::{
+    begin_swap, book_borrow, book_repay, borrow_amount_to_shares, borrow_shares_to_amount, check_health,
+    error::ErrorCode, health_assets, load_health_accounts, swap_via_adapter, transfer_from_treasure,
+    transfer_to_treasure, Bank, BorrowEvent, HealthAsset, RepayEvent, Rounding, User,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapDebt<'info> {
+    #[account(mut)]
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"user",user_account.owner.as_ref(),&[user_account.index]],
+        bump,
+        constraint = user_account.is_owner_or_delegate(&signer.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub user_account:Box<Account<'info,User>>,
+    // mint of the debt that is opened
+    pub new_debt_mint:Box<InterfaceAccount<'info,Mint>>,
+    // mint of the debt that is paid off
+    #[account(constraint = old_debt_mint.key() != new_debt_mint.key() @ ErrorCode::SameMint)]
+    pub old_debt_mint:Box<InterfaceAccount<'info,Mint>>,
+    #[account(
+        mut,
+        seeds=[b"bank",new_debt_mint.key().as_ref()],
+        bump,
+        constraint = !new_debt_bank.paused @ ErrorCode::BankPaused,
+    )]
+    pub new_debt_bank:Box<Account<'info,Bank>>,
+    #[account(
+        mut,
+        seeds=[b"treasure",new_debt_mint.key().as_ref()],
+        token::mint=new_debt_mint,
+        token::authority=new_debt_token_bank,
+        token::token_program = token_program,
+        bump
+    )]
+    pub new_debt_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        seeds=[b"bank",old_debt_mint.key().as_ref()],
+        bump
+    )]
+    pub old_debt_bank:Box<Account<'info,Bank>>,
+    #[account(
+        mut,
+        seeds=[b"treasure",old_debt_mint.key().as_ref()],
+        token::mint=old_debt_mint,
+        token::authority=old_debt_token_bank,
+        token::token_program = token_program,
+        bump
+    )]
+    pub old_debt_token_bank:Box<InterfaceAccount<'info,TokenAccount>>,
+    // the borrowed tokens land here and are the input of the swap
+    #[account(
+        mut,
+        token::mint=new_debt_mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_new_debt_account:Box<InterfaceAccount<'info,TokenAccount>>,
+    // the swap output lands here and repays the old debt, anything above the debt stays with the signer
+    #[account(
+        mut,
+        token::mint=old_debt_mint,
+        token::authority=signer,
+        token::token_program=token_program,
+    )]
+    pub signer_old_debt_account:Box<InterfaceAccount<'info,TokenAccount>>,
+    // price accounts for the two mints, a Pyth price account holds a single feed
+    pub new_debt_price_update:Box<Account<'info,PriceUpdateV2>>,
+    pub old_debt_price_update:Box<Account<'info,PriceUpdateV2>>,
+    /// CHECK: must be the new debt bank's configured swap program, checked in the handler
+    pub swap_program:UncheckedAccount<'info>,
+    pub token_program:Interface<'info,TokenInterface>
+    // remaining accounts: a (bank, price update) pair for each other bank the position uses,
+    // see load_health_accounts, then the accounts of the swap
+}
+
+// 1. Borrow `borrow_amount` from the new debt bank to the signer.
+// 2. Swap it to the old debt mint through the swap program.
+// 3. Repay the old debt with the output and check the position is still healthy.
+//
+// This doesn't go through flash_borrow/flash_repay: the borrowed tokens are never paid back in this
+// transaction, they become the user's new debt. Booking them with book_borrow charges the origination
+// fee, counts them against the borrow cap and the outflow limit, and the final health check covers them.
+// A flash loan on top would charge flash_loan_fee for the same tokens a second time.
+
+pub fn process_swap_debt<'info>(
+    mut ctx:Context<'_, '_, 'info, 'info, SwapDebt<'info>>,
+    borrow_amount:u64,
+    min_repay_amount:u64,
+    swap_data:Vec<u8>,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts;
+    let interest_events = begin_swap(
+        &accounts.user_account,
+        &accounts.signer.key(),
+        &accounts.swap_program.key(),
+        &mut accounts.new_debt_bank,
+        &mut accounts.old_debt_bank,
+    )?;
+
+    let new_key = accounts.new_debt_mint.key();
+    let old_key = accounts.old_debt_mint.key();
+    let now = Clock::get()?.unix_timestamp;
+    let (others, adapter_accounts) = load_health_accounts(&accounts.user_account, &[old_key, new_key], ctx.remaining_accounts)?;
+
+    if accounts.user_account.borrow_shares(&old_key) == 0 {
+        return Err(ErrorCode::NoBorrowsFound.into());
+    }
+
+    let (origination_fee, borrow_shares) = book_borrow(&mut accounts.new_debt_bank, &mut accounts.user_account, &new_key, borrow_amount)?;
+    accounts.new_debt_bank.record_outflow(borrow_amount, now)?;
+    transfer_from_treasure(
+        &accounts.token_program,
+        &accounts.new_debt_token_bank,
+        accounts.signer_new_debt_account.to_account_info(),
+        &accounts.new_debt_mint,
+        ctx.bumps.new_debt_token_bank,
+        borrow_amount,
+    )?;
+
+    let swapped = swap_via_adapter(
+        &accounts.swap_program.to_account_info(),
+        adapter_accounts,
+        &swap_data,
+        borrow_amount,
+        min_repay_amount,
+        &mut accounts.signer_old_debt_account,
+    )?;
+
+    // Repay at most the whole old debt
+    let debt_shares = accounts.user_account.borrow_shares(&old_key);
+    let debt = borrow_shares_to_amount(&accounts.old_debt_bank, debt_shares, Rounding::Up)?;
+    let (repay_amount, repay_shares) = if swapped >= debt {
+        (debt, debt_shares)
+    } else {
+        (swapped, borrow_amount_to_shares(&accounts.old_debt_bank, swapped, Rounding::Down)?)
+    };
+    transfer_to_treasure(
+        &accounts.token_program,
+        accounts.signer_old_debt_account.to_account_info(),
+        &accounts.old_debt_token_bank,
+        accounts.signer.to_account_info(),
+        &accounts.old_debt_mint,
+        repay_amount,
+    )?;
+    book_repay(&mut accounts.old_debt_bank, &mut accounts.user_account, &old_key, repay_amount, repay_shares)?;
+    accounts.old_debt_bank.record_inflow(repay_amount, now);
+
+    check_health(
+        &accounts.user_account,
+        &health_assets(
+            &[
+                HealthAsset { bank: &accounts.new_debt_bank, price_update: &accounts.new_debt_price_update },
+                HealthAsset { bank: &accounts.old_debt_bank, price_update: &accounts.old_debt_price_update },
+            ],
+            &others,
+        ),
+    )?;
+
+    msg!(
+        "Debt swap completed: User: {}, Borrowed: {} of {}, Repaid: {} of {}",
+        accounts.user_account.key(),
+        borrow_amount,
+        new_key,
+        repay_amount,
+        old_key
+    );
+
+    let signer = accounts.signer.key();
+    let borrow_event = BorrowEvent::new(&accounts.user_account, signer, &accounts.new_debt_bank, borrow_amount, origination_fee, borrow_shares, now);
+    let repay_event = RepayEvent::new(&accounts.user_account, signer, &accounts.old_debt_bank, repay_amount, repay_shares, now);
+    for event in interest_events.into_iter().filter(|event| event.interest > 0) {
+        emit_cpi!(event);
+    }
+    emit_cpi!(borrow_event);
+    emit_cpi!(repay_event);
+
+    Ok(())
+}
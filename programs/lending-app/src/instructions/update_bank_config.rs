@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{error::ErrorCode, Bank, BankConfigChangedEvent, BankConfigUpdate};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateBankConfig<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"bank",mint.key().as_ref()],
+        bump,
+        constraint = bank.authority == signer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub bank:Account<'info,Bank>,
+    pub mint:InterfaceAccount<'info,Mint>,
+}
+
+// 1. Split the update into tightening and loosening changes.
+// 2. Tightening changes are applied right away.
+// 3. Loosening changes (higher max_ltv / liquidation_threshold / caps / outflow limit,
+//    shorter delay or outflow window, new swap program or price feed) are queued and can only be applied after `config_delay` seconds.
+
+pub fn process_update_bank_config(ctx:Context<UpdateBankConfig>, update:BankConfigUpdate)->Result<()>{
+    let bank = &mut ctx.accounts.bank;
+    let now = Clock::get()?.unix_timestamp;
+    let immediate = bank.update_config(&update, now)?;
+    if !bank.pending_config.is_empty() {
+        msg!("Config change queued, can be applied at {}", bank.pending_config_ready_at);
+    }
+
+    emit_cpi!(BankConfigChangedEvent {
+        mint:ctx.accounts.mint.key(),
+        signer:ctx.accounts.signer.key(),
+        applied:immediate,
+        pending:ctx.accounts.bank.pending_config,
+        pending_ready_at:ctx.accounts.bank.pending_config_ready_at,
+        timestamp:now,
+    });
+
+    Ok(())
+}
@@ -1,29 +1,34 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{token_2022::{transfer_checked,TransferChecked}, token_interface::*};
+use anchor_spl::token_interface::*;
 
-use crate::{error::ErrorCode, Bank, User};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
+use crate::{accrue_bank_interest, book_withdraw, check_health, health_assets, load_health_accounts, transfer_from_treasure, HealthAsset, InterestAccruedEvent, WithdrawEvent, deposit_amount_to_shares, deposit_shares_to_amount, error::ErrorCode, Bank, Rounding, User};
+
+#[event_cpi]
 #[derive(Accounts)]
 pub struct WithDraw<'info> {
     #[account(mut)]
     pub signer:Signer<'info>,
+    // any token account of the bank's mint, e.g. one owned by another protocol
     #[account(
         mut,
-        associated_token::mint=mint,
-        associated_token::authority=signer,
-        associated_token::token_program=token_program,
+        token::mint=mint,
+        token::token_program=token_program,
     )]
-    pub  user_token_account:InterfaceAccount<'info,TokenAccount>,
+    pub  recipient_token_account:InterfaceAccount<'info,TokenAccount>,
     #[account(
         mut,
-        seeds=[b"user", signer.key().as_ref()],
-        bump
+        seeds=[b"user",user_account.owner.as_ref(),&[user_account.index]],
+        bump,
+        constraint = user_account.is_owner_or_delegate(&signer.key()) @ ErrorCode::Unauthorized,
     )]
     pub user_account:Account<'info,User>,
     #[account(
         mut,
         seeds=[b"bank",mint.key().as_ref()],
-        bump
+        bump,
+        constraint = !bank.paused @ ErrorCode::BankPaused,
     )]
     pub  bank:Account<'info,Bank>,
     #[account(
@@ -36,8 +41,11 @@ pub struct WithDraw<'info> {
     )]
     pub  bank_token_account:InterfaceAccount<'info,TokenAccount>,
     pub  mint:InterfaceAccount<'info,Mint>,
+    pub price_update:Box<Account<'info,PriceUpdateV2>>,
     pub system_program:Program<'info,System>,
     pub token_program:Interface<'info,TokenInterface>
+    // remaining accounts: a (bank, price update) pair for each other bank the position uses,
+    // see load_health_accounts. Only needed when the position has debt.
 }
 
 // 1. Create a transaction instruction and do a CPI transfer.
@@ -46,10 +54,17 @@ pub struct WithDraw<'info> {
 
 
 pub fn process_withdraw(ctx: Context<WithDraw>, amount: u64) -> Result<()> {
-    let user_token_account = &mut ctx.accounts.user_token_account;
-    let user_account = &mut ctx.accounts.user_account;
-    let bank_account = &mut ctx.accounts.bank;
-    let mint_key = ctx.accounts.mint.key();
+    // u64::MAX means "everything I have": redeem all of the user's shares instead of an amount.
+    if amount == u64::MAX {
+        let user_shares = ctx.accounts.user_account.deposit_shares(&ctx.accounts.mint.key());
+        return process_redeem_shares(ctx, user_shares);
+    }
+
+    let interest = accrue_bank_interest(&mut ctx.accounts.bank)?;
+    if interest > 0 {
+        emit_cpi!(InterestAccruedEvent::new(&ctx.accounts.bank, interest));
+    }
+    let bank_account = &ctx.accounts.bank;
 
     // ✅ VALIDATION 1: Check if bank has sufficient funds
     if amount > bank_account.total_deposits {
@@ -61,122 +76,116 @@ pub fn process_withdraw(ctx: Context<WithDraw>, amount: u64) -> Result<()> {
         return Err(ErrorCode::InvalidBankState.into());
     }
 
-    // ✅ VALIDATION 3: Get user's current shares and validate ownership
-    let user_current_shares = if user_account.mint_address == ctx.accounts.mint.key() {
-        user_account.deposited_usdc_shares
-    } else {
-        user_account.deposited_sol_shares
-    };
+    // ✅ VALIDATION 3: Check minimum withdrawal amount (optional, adjust as needed)
+    if amount == 0 {
+        return Err(ErrorCode::InvalidWithdrawalAmount.into());
+    }
+
+    // Calculate proportional shares to remove, rounded up so the user never burns less than they take out
+    let shares_to_remove = deposit_amount_to_shares(bank_account, amount, Rounding::Up)?;
 
-    // ✅ VALIDATION 4: Check if user has any deposits
-    if user_current_shares == 0 {
-        return Err(ErrorCode::NoDepositsFound.into());
+    withdraw_from_bank(ctx, amount, shares_to_remove)
+}
+
+// Same as `withdraw`, but the user names the number of shares to burn
+// and receives whatever they are currently worth.
+pub fn process_redeem_shares(ctx: Context<WithDraw>, share_amount: u64) -> Result<()> {
+    let interest = accrue_bank_interest(&mut ctx.accounts.bank)?;
+    if interest > 0 {
+        emit_cpi!(InterestAccruedEvent::new(&ctx.accounts.bank, interest));
     }
+    let bank_account = &ctx.accounts.bank;
 
-    // ✅ VALIDATION 5: Calculate maximum withdrawable amount based on user's shares
-    let max_withdrawable = (user_current_shares as u128)
-        .checked_mul(bank_account.total_deposits as u128)
-        .ok_or(ErrorCode::MathError)?
-        .checked_div(bank_account.total_deposit_shares as u128)
-        .ok_or(ErrorCode::MathError)? as u64;
+    if bank_account.total_deposit_shares == 0 {
+        return Err(ErrorCode::InvalidBankState.into());
+    }
 
-    // ✅ VALIDATION 6: Ensure withdrawal amount doesn't exceed user's entitlement
-    if amount > max_withdrawable {
-        return Err(ErrorCode::ExceedsMaxWithdrawal.into());
+    if share_amount == 0 {
+        return Err(ErrorCode::InvalidWithdrawalAmount.into());
     }
 
-    // ✅ VALIDATION 7: Check minimum withdrawal amount (optional, adjust as needed)
+    let amount = deposit_shares_to_amount(bank_account, share_amount, Rounding::Down)?;
+
     if amount == 0 {
         return Err(ErrorCode::InvalidWithdrawalAmount.into());
     }
 
-    // Calculate proportional shares to remove
-    let shares_to_remove = (amount as u128)
-        .checked_mul(bank_account.total_deposit_shares as u128)
-        .ok_or(ErrorCode::MathError)?
-        .checked_div(bank_account.total_deposits as u128)
-        .ok_or(ErrorCode::MathError)? as u64;
+    withdraw_from_bank(ctx, amount, share_amount)
+}
+
+fn withdraw_from_bank(ctx: Context<WithDraw>, amount: u64, shares_to_remove: u64) -> Result<()> {
+    let recipient_token_account = &mut ctx.accounts.recipient_token_account;
+    let user_account = &mut ctx.accounts.user_account;
+    let bank_account = &mut ctx.accounts.bank;
+    let mint_key = ctx.accounts.mint.key();
+
+    // ✅ VALIDATION 4: Get user's current shares and validate ownership
+    let user_current_shares = user_account.deposit_shares(&mint_key);
+
+    // ✅ VALIDATION 5: Check if user has any deposits
+    if user_current_shares == 0 {
+        return Err(ErrorCode::NoDepositsFound.into());
+    }
+
+    // ✅ VALIDATION 6: Calculate maximum withdrawable amount based on user's shares
+    let max_withdrawable = deposit_shares_to_amount(bank_account, user_current_shares, Rounding::Down)?;
+
+    // ✅ VALIDATION 7: Ensure withdrawal amount doesn't exceed user's entitlement
+    if amount > max_withdrawable {
+        return Err(ErrorCode::ExceedsMaxWithdrawal.into());
+    }
 
     // ✅ VALIDATION 8: Double-check shares calculation
     if shares_to_remove > user_current_shares {
         return Err(ErrorCode::InsufficientShares.into());
     }
 
-    // ✅ VALIDATION 9: Check user's token balance for the specific token type
-    let (user_current_amount, user_current_share_balance) = if user_account.mint_address == ctx.accounts.mint.key() {
-        (user_account.deposited_usdc, user_account.deposited_usdc_shares)
-    } else {
-        (user_account.deposited_sol, user_account.deposited_sol_shares)
-    };
-
-    if amount > user_current_amount {
-        return Err(ErrorCode::InsufficientUserBalance.into());
-    }
+    // ✅ VALIDATION 9: A delegate without receive rights can only withdraw to the owner
+    user_account.check_recipient(&ctx.accounts.signer.key(), &recipient_token_account.owner)?;
 
-    // Derive signer seeds for PDA authority
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        b"treasure",
-        mint_key.as_ref(),
-        &[ctx.bumps.bank_token_account],
-    ]];
+    // ✅ VALIDATION 10: Bound how much can leave the vault per window
+    bank_account.record_outflow(amount, Clock::get()?.unix_timestamp)?;
 
-    // ✅ SECURE TRANSFER: CPI transfer (SPL token withdraw)
-    let cpi_context = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        TransferChecked {
-            from: ctx.accounts.bank_token_account.to_account_info(),
-            to: user_token_account.to_account_info(),
-            authority: ctx.accounts.bank_token_account.to_account_info(),
-            mint: ctx.accounts.mint.to_account_info(),
-        },
-        signer_seeds,
-    );
+    book_withdraw(bank_account, user_account, &mint_key, amount, shares_to_remove)?;
 
-    transfer_checked(cpi_context, amount, ctx.accounts.mint.decimals)?;
-
-    // ✅ SECURE STATE UPDATES: Only update state after successful transfer
-    
-    // Update Bank totals
-    bank_account.total_deposits = bank_account
-        .total_deposits
-        .checked_sub(amount)
-        .ok_or(ErrorCode::MathError)?;
-    bank_account.total_deposit_shares = bank_account
-        .total_deposit_shares
-        .checked_sub(shares_to_remove)
-        .ok_or(ErrorCode::MathError)?;
-
-    // Update User balances
-    if user_account.mint_address == ctx.accounts.mint.key() {
-        // Updating USDC Balances
-        user_account.deposited_usdc = user_account
-            .deposited_usdc
-            .checked_sub(amount)
-            .ok_or(ErrorCode::MathError)?;
-        user_account.deposited_usdc_shares = user_account
-            .deposited_usdc_shares
-            .checked_sub(shares_to_remove)
-            .ok_or(ErrorCode::MathError)?;
-    } else {
-        // Updating SOL Balances
-        user_account.deposited_sol = user_account
-            .deposited_sol
-            .checked_sub(amount)
-            .ok_or(ErrorCode::MathError)?;
-        user_account.deposited_sol_shares = user_account
-            .deposited_sol_shares
-            .checked_sub(shares_to_remove)
-            .ok_or(ErrorCode::MathError)?;
+    // ✅ VALIDATION 11: What is left has to cover the debt. A position without debt needs no prices.
+    let user_account = &ctx.accounts.user_account;
+    if user_account.has_debt() {
+        let (others, _) = load_health_accounts(user_account, &[mint_key], ctx.remaining_accounts)?;
+        check_health(
+            user_account,
+            &health_assets(&[HealthAsset { bank: &ctx.accounts.bank, price_update: &ctx.accounts.price_update }], &others),
+        )?;
     }
 
-    // ✅ OPTIONAL: Emit withdrawal event for tracking
+    // ✅ SECURE TRANSFER: CPI transfer (SPL token withdraw)
+    transfer_from_treasure(
+        &ctx.accounts.token_program,
+        &ctx.accounts.bank_token_account,
+        ctx.accounts.recipient_token_account.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.bumps.bank_token_account,
+        amount,
+    )?;
+
     msg!(
-        "Withdrawal completed: User: {}, Amount: {}, Shares removed: {}, Max withdrawable was: {}",
+        "Withdrawal completed: User: {}, Recipient: {}, Amount: {}, Shares removed: {}, Max withdrawable was: {}",
         ctx.accounts.signer.key(),
+        ctx.accounts.recipient_token_account.key(),
         amount,
         shares_to_remove,
         max_withdrawable
     );
 
+    // ✅ Emit withdrawal event for indexers
+    emit_cpi!(WithdrawEvent::new(
+        &ctx.accounts.user_account,
+        ctx.accounts.signer.key(),
+        &ctx.accounts.bank,
+        amount,
+        shares_to_remove,
+        ctx.accounts.bank.last_updated,
+    ));
+
     Ok(())
 }
@@ -1,11 +1,17 @@
+// `#[program]` generates the IDL resize handler at crate level with the deprecated
+// `AccountInfo::realloc`, so the lint can't be scoped to anything narrower than the crate.
+#![allow(deprecated)]
+
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -15,20 +21,22 @@ declare_id!("DJhkrJXTWBWbTMJ1Eots37WNhb2NZR5Ytchz8MTBNUzp");
 pub mod lending_app {
     use super::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn init_bank(
     ctx: Context<InitializeBank>,
     max_ltv:u64,
-    mint_address:Pubkey,
     liquidation_threshold:u64,
     liquidation_bonus:u64,
     close_factor:u64,
-    interest_rate:u64
+    interest_rate:u64,
+    config_delay:i64,
+    price_feed_id:[u8;32],
 ) -> Result<()> {
-    instructions::process_init_bank(ctx,max_ltv,mint_address,liquidation_threshold,liquidation_bonus,close_factor,interest_rate)?;
+    instructions::process_init_bank(ctx,max_ltv,liquidation_threshold,liquidation_bonus,close_factor,interest_rate,config_delay,price_feed_id)?;
     Ok(())
 }
-pub fn init_user (ctx:Context<InitializeUser>)->Result<()> {
-  instructions::process_init_user(ctx)?;
+pub fn init_user (ctx:Context<InitializeUser>, index:u8)->Result<()> {
+  instructions::process_init_user(ctx, index)?;
    Ok(())
 }
 pub fn deposit(ctx:Context<Deposit>, amount:u64)->Result<()>{
@@ -36,11 +44,31 @@ pub fn deposit(ctx:Context<Deposit>, amount:u64)->Result<()>{
     Ok(())
 }
 
+pub fn deposit_exact_shares(ctx:Context<Deposit>, shares:u64, max_amount:u64)->Result<()>{
+    instructions::process_deposit_exact_shares(ctx, shares, max_amount)?;
+    Ok(())
+}
+
 pub fn withdraw(ctx:Context<WithDraw>,amount:u64)-> Result<()>{
     instructions::process_withdraw(ctx, amount)?;
     Ok(())
 }
 
+pub fn redeem_shares(ctx:Context<WithDraw>, share_amount:u64)-> Result<()>{
+    instructions::process_redeem_shares(ctx, share_amount)?;
+    Ok(())
+}
+
+pub fn supply(ctx:Context<Supply>, amount:u64)->Result<()>{
+    instructions::process_supply(ctx, amount)?;
+    Ok(())
+}
+
+pub fn redeem_receipts(ctx:Context<RedeemReceipts>, shares:u64)->Result<()>{
+    instructions::process_redeem_receipts(ctx, shares)?;
+    Ok(())
+}
+
 pub fn borrow(ctx:Context<Borrow>, amount:u64)->Result<()>{
     instructions::process_borrow(ctx, amount)?;
     Ok(())
@@ -57,5 +85,107 @@ pub fn liquidate(ctx:Context<Liquidate>)->Result<()>{
     
 }
 
+pub fn flash_borrow(ctx:Context<FlashBorrow>, amount:u64)->Result<()>{
+    instructions::process_flash_borrow(ctx, amount)?;
+    Ok(())
+}
+
+pub fn flash_repay(ctx:Context<FlashRepay>)->Result<()>{
+    instructions::process_flash_repay(ctx)?;
+    Ok(())
+}
+
+pub fn collect_protocol_fees(ctx:Context<CollectProtocolFees>)->Result<()>{
+    instructions::process_collect_protocol_fees(ctx)?;
+    Ok(())
+}
+
+pub fn repay_with_collateral<'info>(
+    ctx:Context<'_, '_, 'info, 'info, RepayWithCollateral<'info>>,
+    collateral_amount:u64,
+    min_repay_amount:u64,
+    swap_data:Vec<u8>,
+)->Result<()>{
+    instructions::process_repay_with_collateral(ctx, collateral_amount, min_repay_amount, swap_data)?;
+    Ok(())
+}
+
+pub fn loop_leverage<'info>(
+    ctx:Context<'_, '_, 'info, 'info, LoopLeverage<'info>>,
+    borrow_amount:u64,
+    min_amount_out:u64,
+    max_iterations:u8,
+    target_leverage_bps:u64,
+    swap_data:Vec<u8>,
+)->Result<()>{
+    instructions::process_loop_leverage(ctx, borrow_amount, min_amount_out, max_iterations, target_leverage_bps, swap_data)?;
+    Ok(())
+}
+
+pub fn swap_collateral<'info>(
+    ctx:Context<'_, '_, 'info, 'info, SwapCollateral<'info>>,
+    amount:u64,
+    min_amount_out:u64,
+    swap_data:Vec<u8>,
+)->Result<()>{
+    instructions::process_swap_collateral(ctx, amount, min_amount_out, swap_data)?;
+    Ok(())
+}
+
+pub fn swap_debt<'info>(
+    ctx:Context<'_, '_, 'info, 'info, SwapDebt<'info>>,
+    borrow_amount:u64,
+    min_repay_amount:u64,
+    swap_data:Vec<u8>,
+)->Result<()>{
+    instructions::process_swap_debt(ctx, borrow_amount, min_repay_amount, swap_data)?;
+    Ok(())
+}
+
+pub fn close_user(ctx:Context<CloseUser>)->Result<()>{
+    instructions::process_close_user(ctx)?;
+    Ok(())
+}
+
+pub fn close_bank(ctx:Context<CloseBank>)->Result<()>{
+    instructions::process_close_bank(ctx)?;
+    Ok(())
+}
+
+pub fn set_guardian(ctx:Context<SetGuardian>, guardian:Pubkey)->Result<()>{
+    instructions::process_set_guardian(ctx, guardian)?;
+    Ok(())
+}
+
+pub fn set_paused(ctx:Context<SetPaused>, paused:bool)->Result<()>{
+    instructions::process_set_paused(ctx, paused)?;
+    Ok(())
+}
+
+pub fn update_bank_config(ctx:Context<UpdateBankConfig>, update:BankConfigUpdate)->Result<()>{
+    instructions::process_update_bank_config(ctx, update)?;
+    Ok(())
+}
+
+pub fn apply_bank_config(ctx:Context<ApplyBankConfig>)->Result<()>{
+    instructions::process_apply_bank_config(ctx)?;
+    Ok(())
+}
+
+pub fn cancel_bank_config(ctx:Context<CancelBankConfig>)->Result<()>{
+    instructions::process_cancel_bank_config(ctx)?;
+    Ok(())
+}
+
+pub fn set_delegate(ctx:Context<SetDelegate>, delegate:Pubkey, delegate_can_receive:bool)->Result<()>{
+    instructions::process_set_delegate(ctx, delegate, delegate_can_receive)?;
+    Ok(())
+}
+
+pub fn reduce_bank_caps(ctx:Context<ReduceBankCaps>, deposit_cap:Option<u64>, borrow_cap:Option<u64>)->Result<()>{
+    instructions::process_reduce_bank_caps(ctx, deposit_cap, borrow_cap)?;
+    Ok(())
+}
+
 
 }
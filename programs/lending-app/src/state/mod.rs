@@ -1,21 +1,111 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::ErrorCode, MAX_FLASH_LOAN_FEE, MAX_POSITIONS, MIN_CONFIG_DELAY, VIRTUAL_DEPOSIT_ASSETS, VIRTUAL_DEPOSIT_SHARES};
+
 #[account]
 #[derive(InitSpace)]
 pub struct User {
-    // SOL
-   pub deposited_sol:u64,
-   pub deposited_sol_shares:u64,
-   pub borrowed_sol:u64,
-   pub borrowed_sol_shares:u64,
-   //USDC
-   pub deposited_usdc:u64,
-   pub deposited_usdc_shares:u64,
-   pub borrowed_usdc:u64,
-   pub borrowed_usdc_shares:u64,
-   // USDC Mint Address     
-   pub mint_address:Pubkey,
+   // one slot per bank the position holds deposits or debt in
+   pub positions:[Position; MAX_POSITIONS],
    pub health_factor:u64,
+   // wallet that owns the position
+   pub owner:Pubkey,
+   // optional manager that can operate the position (Pubkey::default() = none)
+   pub delegate:Pubkey,
+   // whether the delegate may withdraw / borrow to accounts it owns itself
+   pub delegate_can_receive:bool,
+   // sub-account number, part of the PDA seeds
+   pub index:u8,
+}
+
+// Deposits and debt of a position in the bank of `mint`. A default `mint` marks a free slot.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct Position {
+  pub mint:Pubkey,
+  pub deposited:u64,
+  pub deposited_shares:u64,
+  pub borrowed:u64,
+  pub borrowed_shares:u64,
+}
+
+impl Position {
+  pub fn is_empty(&self) -> bool {
+    self.deposited == 0 && self.deposited_shares == 0 && self.borrowed == 0 && self.borrowed_shares == 0
+  }
+}
+
+impl User {
+  pub fn is_owner_or_delegate(&self, key:&Pubkey) -> bool {
+    *key == self.owner || (self.delegate != Pubkey::default() && *key == self.delegate)
+  }
+
+  pub fn position(&self, mint:&Pubkey) -> Option<&Position> {
+    self.open_positions().find(|position| position.mint == *mint)
+  }
+
+  // Slots in use, in slot order.
+  pub fn open_positions(&self) -> impl Iterator<Item = &Position> {
+    self.positions.iter().filter(|position| position.mint != Pubkey::default())
+  }
+
+  // The slot of `mint`, taking a free one the first time the position uses the bank.
+  fn position_mut(&mut self, mint:&Pubkey) -> Result<&mut Position> {
+    let index = self.positions.iter().position(|position| position.mint == *mint)
+      .or_else(|| self.positions.iter().position(|position| position.mint == Pubkey::default()))
+      .ok_or(ErrorCode::TooManyPositions)?;
+    let position = &mut self.positions[index];
+    position.mint = *mint;
+    Ok(position)
+  }
+
+  // (amount, shares) deposited in the bank of `mint`.
+  pub fn deposit_position(&mut self, mint:&Pubkey) -> Result<(&mut u64, &mut u64)> {
+    let position = self.position_mut(mint)?;
+    Ok((&mut position.deposited, &mut position.deposited_shares))
+  }
+
+  // (amount, shares) borrowed from the bank of `mint`.
+  pub fn borrow_position(&mut self, mint:&Pubkey) -> Result<(&mut u64, &mut u64)> {
+    let position = self.position_mut(mint)?;
+    Ok((&mut position.borrowed, &mut position.borrowed_shares))
+  }
+
+  // Frees the slot of `mint` once nothing is left in it.
+  pub fn release_position(&mut self, mint:&Pubkey) {
+    if let Some(position) = self.positions.iter_mut().find(|position| position.mint == *mint) {
+      if position.is_empty() {
+        *position = Position::default();
+      }
+    }
+  }
+
+  pub fn deposited(&self, mint:&Pubkey) -> u64 {
+    self.position(mint).map_or(0, |position| position.deposited)
+  }
+
+  pub fn borrowed(&self, mint:&Pubkey) -> u64 {
+    self.position(mint).map_or(0, |position| position.borrowed)
+  }
+
+  pub fn deposit_shares(&self, mint:&Pubkey) -> u64 {
+    self.position(mint).map_or(0, |position| position.deposited_shares)
+  }
+
+  pub fn borrow_shares(&self, mint:&Pubkey) -> u64 {
+    self.position(mint).map_or(0, |position| position.borrowed_shares)
+  }
+
+  pub fn has_debt(&self) -> bool {
+    self.open_positions().any(|position| position.borrowed_shares != 0)
+  }
+
+  // A delegate that is not allowed to receive funds can only send them back to the owner.
+  pub fn check_recipient(&self, signer:&Pubkey, recipient_owner:&Pubkey) -> Result<()> {
+    if *signer != self.owner && !self.delegate_can_receive && *recipient_owner != self.owner {
+      return Err(ErrorCode::DelegateRecipientNotAllowed.into());
+    }
+    Ok(())
+  }
 }
 
 #[account]
@@ -34,4 +124,610 @@ pub struct Bank {
   pub max_ltv:u64,
   pub interest_rate:u64,
   pub last_updated:i64,
-}
\ No newline at end of file
+// emergency key that can pause the bank but not change its risk parameters.
+// A pause stops deposits, withdrawals and new borrows; repays and liquidations keep working.
+  pub guardian:Pubkey,
+  pub paused:bool,
+// seconds a loosening config change has to wait before it can be applied
+  pub config_delay:i64,
+  pub pending_config:BankConfigUpdate,
+  pub pending_config_ready_at:i64,
+// upper bounds on total_deposits / total_borrowed (u64::MAX = uncapped)
+  pub deposit_cap:u64,
+  pub borrow_cap:u64,
+// net outflow (withdraw + borrow + liquidation + flash loan - deposit - repay) allowed per `outflow_window` seconds
+  pub outflow_limit:u64,
+  pub outflow_window:i64,
+  pub outflow_window_start:i64,
+  pub outflow_prev_window:u64,
+  pub outflow_cur_window:u64,
+// fee charged on flash loans (bps) and the amount currently lent out in a flash loan
+  pub flash_loan_fee:u64,
+  pub flash_loan_amount:u64,
+// fee added to the debt on every borrow (bps), and the part of it (bps) kept by the protocol
+  pub origination_fee:u64,
+  pub origination_fee_protocol_share:u64,
+// protocol's cut of fees, held in the treasure vault until collected
+  pub protocol_fees:u64,
+// program allowed as swap adapter for collateral/debt swaps (Pubkey::default() = swaps disabled)
+  pub swap_program:Pubkey,
+// smallest deposit accepted while the bank has no deposit shares, MIN_FIRST_DEPOSIT in the mint's decimals
+  pub min_first_deposit:u64,
+// decimals of the mint, for pricing positions without loading the mint
+  pub decimals:u8,
+// Pyth feed the bank's positions are priced with
+  pub price_feed_id:[u8;32],
+}
+
+// Risk parameters that can be changed after the bank is created.
+// `None` leaves the current value untouched.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct BankConfigUpdate {
+  pub max_ltv:Option<u64>,
+  pub liquidation_threshold:Option<u64>,
+  pub liquidation_bonus:Option<u64>,
+  pub close_factor:Option<u64>,
+  pub interest_rate:Option<u64>,
+  pub config_delay:Option<i64>,
+  pub deposit_cap:Option<u64>,
+  pub borrow_cap:Option<u64>,
+  pub outflow_limit:Option<u64>,
+  pub outflow_window:Option<i64>,
+  pub flash_loan_fee:Option<u64>,
+  pub origination_fee:Option<u64>,
+  pub origination_fee_protocol_share:Option<u64>,
+  pub swap_program:Option<Pubkey>,
+  pub price_feed_id:Option<[u8;32]>,
+}
+
+impl BankConfigUpdate {
+  pub fn is_empty(&self) -> bool {
+    self.max_ltv.is_none()
+      && self.liquidation_threshold.is_none()
+      && self.liquidation_bonus.is_none()
+      && self.close_factor.is_none()
+      && self.interest_rate.is_none()
+      && self.config_delay.is_none()
+      && self.deposit_cap.is_none()
+      && self.borrow_cap.is_none()
+      && self.outflow_limit.is_none()
+      && self.outflow_window.is_none()
+      && self.flash_loan_fee.is_none()
+      && self.origination_fee.is_none()
+      && self.origination_fee_protocol_share.is_none()
+      && self.swap_program.is_none()
+      && self.price_feed_id.is_none()
+  }
+
+  // Overwrites the fields that are set in `other`.
+  pub fn merge(&mut self, other:&BankConfigUpdate) {
+    if other.max_ltv.is_some() { self.max_ltv = other.max_ltv; }
+    if other.liquidation_threshold.is_some() { self.liquidation_threshold = other.liquidation_threshold; }
+    if other.liquidation_bonus.is_some() { self.liquidation_bonus = other.liquidation_bonus; }
+    if other.close_factor.is_some() { self.close_factor = other.close_factor; }
+    if other.interest_rate.is_some() { self.interest_rate = other.interest_rate; }
+    if other.config_delay.is_some() { self.config_delay = other.config_delay; }
+    if other.deposit_cap.is_some() { self.deposit_cap = other.deposit_cap; }
+    if other.borrow_cap.is_some() { self.borrow_cap = other.borrow_cap; }
+    if other.outflow_limit.is_some() { self.outflow_limit = other.outflow_limit; }
+    if other.outflow_window.is_some() { self.outflow_window = other.outflow_window; }
+    if other.flash_loan_fee.is_some() { self.flash_loan_fee = other.flash_loan_fee; }
+    if other.origination_fee.is_some() { self.origination_fee = other.origination_fee; }
+    if other.origination_fee_protocol_share.is_some() { self.origination_fee_protocol_share = other.origination_fee_protocol_share; }
+    if other.swap_program.is_some() { self.swap_program = other.swap_program; }
+    if other.price_feed_id.is_some() { self.price_feed_id = other.price_feed_id; }
+  }
+
+  // Drops the fields that are set in `other`.
+  pub fn remove(&mut self, other:&BankConfigUpdate) {
+    if other.max_ltv.is_some() { self.max_ltv = None; }
+    if other.liquidation_threshold.is_some() { self.liquidation_threshold = None; }
+    if other.liquidation_bonus.is_some() { self.liquidation_bonus = None; }
+    if other.close_factor.is_some() { self.close_factor = None; }
+    if other.interest_rate.is_some() { self.interest_rate = None; }
+    if other.config_delay.is_some() { self.config_delay = None; }
+    if other.deposit_cap.is_some() { self.deposit_cap = None; }
+    if other.borrow_cap.is_some() { self.borrow_cap = None; }
+    if other.outflow_limit.is_some() { self.outflow_limit = None; }
+    if other.outflow_window.is_some() { self.outflow_window = None; }
+    if other.flash_loan_fee.is_some() { self.flash_loan_fee = None; }
+    if other.origination_fee.is_some() { self.origination_fee = None; }
+    if other.origination_fee_protocol_share.is_some() { self.origination_fee_protocol_share = None; }
+    if other.swap_program.is_some() { self.swap_program = None; }
+    if other.price_feed_id.is_some() { self.price_feed_id = None; }
+  }
+}
+
+impl Bank {
+  // The guardian can pause the bank in an emergency, but only the authority can unpause it again.
+  pub fn set_paused(&mut self, signer:&Pubkey, paused:bool) -> Result<()> {
+    let allowed = *signer == self.authority || (paused && *signer == self.guardian);
+    if !allowed {
+      return Err(ErrorCode::Unauthorized.into());
+    }
+    self.paused = paused;
+    Ok(())
+  }
+
+  pub fn check_swap_program(&self, program:&Pubkey) -> Result<()> {
+    if self.swap_program == Pubkey::default() || self.swap_program != *program {
+      return Err(ErrorCode::InvalidSwapProgram.into());
+    }
+    Ok(())
+  }
+
+  // Deposit totals including the virtual offsets, for share conversions.
+  pub fn virtual_deposit_shares(&self) -> u128 {
+    self.total_deposit_shares as u128 + VIRTUAL_DEPOSIT_SHARES as u128
+  }
+
+  pub fn virtual_total_deposits(&self) -> u128 {
+    self.total_deposits as u128 + VIRTUAL_DEPOSIT_ASSETS as u128
+  }
+
+  // Invariants every bank config has to hold, checked whenever parameters change.
+  pub fn validate_config(&self) -> Result<()> {
+    let valid = self.liquidation_threshold <= 10_000
+      && self.max_ltv < self.liquidation_threshold
+      && self.close_factor <= 10_000
+      && self.liquidation_bonus <= 10_000
+      && self.flash_loan_fee <= MAX_FLASH_LOAN_FEE
+      && self.origination_fee <= 10_000
+      && self.origination_fee_protocol_share <= 10_000
+      && self.config_delay >= MIN_CONFIG_DELAY
+      && self.outflow_window >= 0;
+    if !valid {
+      return Err(ErrorCode::InvalidConfig.into());
+    }
+    Ok(())
+  }
+
+  pub fn apply_config(&mut self, update:&BankConfigUpdate) {
+    if let Some(max_ltv) = update.max_ltv { self.max_ltv = max_ltv; }
+    if let Some(liquidation_threshold) = update.liquidation_threshold { self.liquidation_threshold = liquidation_threshold; }
+    if let Some(liquidation_bonus) = update.liquidation_bonus { self.liquidation_bonus = liquidation_bonus; }
+    if let Some(close_factor) = update.close_factor { self.close_factor = close_factor; }
+    if let Some(interest_rate) = update.interest_rate { self.interest_rate = interest_rate; }
+    if let Some(config_delay) = update.config_delay { self.config_delay = config_delay; }
+    if let Some(deposit_cap) = update.deposit_cap { self.deposit_cap = deposit_cap; }
+    if let Some(borrow_cap) = update.borrow_cap { self.borrow_cap = borrow_cap; }
+    if let Some(outflow_limit) = update.outflow_limit { self.outflow_limit = outflow_limit; }
+    // The outflow already counted stays counted, so changing the window can't reset the limiter mid-drain.
+    if let Some(outflow_window) = update.outflow_window { self.outflow_window = outflow_window; }
+    if let Some(flash_loan_fee) = update.flash_loan_fee { self.flash_loan_fee = flash_loan_fee; }
+    if let Some(origination_fee) = update.origination_fee { self.origination_fee = origination_fee; }
+    if let Some(share) = update.origination_fee_protocol_share { self.origination_fee_protocol_share = share; }
+    if let Some(swap_program) = update.swap_program { self.swap_program = swap_program; }
+    if let Some(price_feed_id) = update.price_feed_id { self.price_feed_id = price_feed_id; }
+  }
+
+  // Tightening changes take effect right away, loosening ones are queued for `config_delay` seconds.
+  // Returns the part of `update` that was applied.
+  pub fn update_config(&mut self, update:&BankConfigUpdate, now:i64) -> Result<BankConfigUpdate> {
+    let mut immediate = BankConfigUpdate {
+      liquidation_bonus:update.liquidation_bonus,
+      close_factor:update.close_factor,
+      interest_rate:update.interest_rate,
+      origination_fee:update.origination_fee,
+      origination_fee_protocol_share:update.origination_fee_protocol_share,
+      ..Default::default()
+    };
+    let mut queued = BankConfigUpdate::default();
+
+    if let Some(max_ltv) = update.max_ltv {
+      if max_ltv > self.max_ltv {
+        queued.max_ltv = Some(max_ltv);
+      } else {
+        immediate.max_ltv = Some(max_ltv);
+      }
+    }
+    if let Some(liquidation_threshold) = update.liquidation_threshold {
+      if liquidation_threshold > self.liquidation_threshold {
+        queued.liquidation_threshold = Some(liquidation_threshold);
+      } else {
+        immediate.liquidation_threshold = Some(liquidation_threshold);
+      }
+    }
+    if let Some(deposit_cap) = update.deposit_cap {
+      if deposit_cap > self.deposit_cap {
+        queued.deposit_cap = Some(deposit_cap);
+      } else {
+        immediate.deposit_cap = Some(deposit_cap);
+      }
+    }
+    if let Some(borrow_cap) = update.borrow_cap {
+      if borrow_cap > self.borrow_cap {
+        queued.borrow_cap = Some(borrow_cap);
+      } else {
+        immediate.borrow_cap = Some(borrow_cap);
+      }
+    }
+    if let Some(outflow_limit) = update.outflow_limit {
+      if outflow_limit > self.outflow_limit {
+        queued.outflow_limit = Some(outflow_limit);
+      } else {
+        immediate.outflow_limit = Some(outflow_limit);
+      }
+    }
+    if let Some(outflow_window) = update.outflow_window {
+      // A shorter window lets the same limit drain the vault faster, 0 turns the limiter off.
+      if outflow_window < self.outflow_window {
+        queued.outflow_window = Some(outflow_window);
+      } else {
+        immediate.outflow_window = Some(outflow_window);
+      }
+    }
+    if let Some(swap_program) = update.swap_program {
+      // Trusting a new swap program is as risky as swapping an oracle, disabling swaps is not.
+      if swap_program != Pubkey::default() {
+        queued.swap_program = Some(swap_program);
+      } else {
+        immediate.swap_program = Some(swap_program);
+      }
+    }
+    // The feed decides what every position in the bank is worth, so a change always waits.
+    queued.price_feed_id = update.price_feed_id;
+    if let Some(flash_loan_fee) = update.flash_loan_fee {
+      if flash_loan_fee > self.flash_loan_fee {
+        queued.flash_loan_fee = Some(flash_loan_fee);
+      } else {
+        immediate.flash_loan_fee = Some(flash_loan_fee);
+      }
+    }
+    if let Some(config_delay) = update.config_delay {
+      if config_delay < self.config_delay {
+        queued.config_delay = Some(config_delay);
+      } else {
+        immediate.config_delay = Some(config_delay);
+      }
+    }
+
+    // A newer tightening value replaces whatever was queued for the same field.
+    self.apply_config(&immediate);
+    self.pending_config.remove(&immediate);
+
+    // Both the config in effect now and the one after the queue is applied must be valid.
+    self.validate_config()?;
+    let mut after_pending = self.clone();
+    let mut pending = self.pending_config;
+    pending.merge(&queued);
+    after_pending.apply_config(&pending);
+    after_pending.validate_config()?;
+
+    if !queued.is_empty() {
+      self.pending_config.merge(&queued);
+      self.pending_config_ready_at = now
+        .checked_add(self.config_delay)
+        .ok_or(ErrorCode::MathOverflow)?;
+    } else if self.pending_config.is_empty() {
+      self.pending_config_ready_at = 0;
+    }
+    Ok(immediate)
+  }
+
+  // Lowers the caps without the timelock, any queued raise of the same cap is dropped.
+  pub fn reduce_caps(&mut self, deposit_cap:Option<u64>, borrow_cap:Option<u64>) -> Result<()> {
+    if deposit_cap.is_some_and(|cap| cap > self.deposit_cap) || borrow_cap.is_some_and(|cap| cap > self.borrow_cap) {
+      return Err(ErrorCode::CapIncrease.into());
+    }
+    if let Some(deposit_cap) = deposit_cap {
+      self.deposit_cap = deposit_cap;
+      self.pending_config.deposit_cap = None;
+    }
+    if let Some(borrow_cap) = borrow_cap {
+      self.borrow_cap = borrow_cap;
+      self.pending_config.borrow_cap = None;
+    }
+    Ok(())
+  }
+
+  // Applies the queued config once its timelock is over, returns what was applied.
+  pub fn apply_pending_config(&mut self, now:i64) -> Result<BankConfigUpdate> {
+    if self.pending_config.is_empty() {
+      return Err(ErrorCode::NoPendingConfig.into());
+    }
+    if now < self.pending_config_ready_at {
+      return Err(ErrorCode::ConfigTimelocked.into());
+    }
+    let pending = self.pending_config;
+    self.apply_config(&pending);
+    // The config may have been tightened since this change was queued.
+    self.validate_config()?;
+    self.pending_config = BankConfigUpdate::default();
+    self.pending_config_ready_at = 0;
+    Ok(pending)
+  }
+
+  // Moves the limiter forward so that `now` falls inside the current window.
+  fn roll_outflow_window(&mut self, now:i64) {
+    let elapsed = now.saturating_sub(self.outflow_window_start);
+    if elapsed >= self.outflow_window.saturating_mul(2) {
+      self.outflow_window_start = now;
+      self.outflow_prev_window = 0;
+      self.outflow_cur_window = 0;
+    } else if elapsed >= self.outflow_window {
+      self.outflow_window_start += self.outflow_window;
+      self.outflow_prev_window = self.outflow_cur_window;
+      self.outflow_cur_window = 0;
+    }
+  }
+
+  // Sliding window estimate: the previous window is weighted by how much of it
+  // still overlaps the last `outflow_window` seconds.
+  fn current_outflow(&self, now:i64) -> Result<u64> {
+    let window = self.outflow_window as u128;
+    let elapsed = now.saturating_sub(self.outflow_window_start) as u128;
+    let prev_weighted = (self.outflow_prev_window as u128)
+      .checked_mul(window.saturating_sub(elapsed))
+      .ok_or(ErrorCode::MathOverflow)?
+      .checked_div(window)
+      .ok_or(ErrorCode::MathOverflow)?;
+    let total = prev_weighted
+      .checked_add(self.outflow_cur_window as u128)
+      .ok_or(ErrorCode::MathOverflow)?;
+    Ok(u64::try_from(total).unwrap_or(u64::MAX))
+  }
+
+  pub fn record_outflow(&mut self, amount:u64, now:i64) -> Result<()> {
+    if self.outflow_window <= 0 {
+      return Ok(());
+    }
+    self.roll_outflow_window(now);
+    let outflow = self.current_outflow(now)?
+      .checked_add(amount)
+      .ok_or(ErrorCode::MathOverflow)?;
+    if outflow > self.outflow_limit {
+      return Err(ErrorCode::OutflowLimitExceeded.into());
+    }
+    self.outflow_cur_window = self.outflow_cur_window
+      .checked_add(amount)
+      .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+  }
+
+  // Inflows free up room in the current window, down to zero.
+  pub fn record_inflow(&mut self, amount:u64, now:i64) {
+    if self.outflow_window <= 0 {
+      return;
+    }
+    self.roll_outflow_window(now);
+    self.outflow_cur_window = self.outflow_cur_window.saturating_sub(amount);
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+
+  const NOW:i64 = 1_700_000_000;
+
+  pub(crate) fn test_bank() -> Bank {
+    Bank {
+      authority:Pubkey::new_unique(),
+      mint_address:Pubkey::new_unique(),
+      total_deposits:0,
+      total_deposit_shares:0,
+      total_borrowed:0,
+      total_borrowed_shares:0,
+      liquidation_threshold:8_000,
+      liquidation_bonus:500,
+      close_factor:5_000,
+      max_ltv:7_500,
+      interest_rate:500,
+      last_updated:NOW,
+      guardian:Pubkey::new_unique(),
+      paused:false,
+      config_delay:MIN_CONFIG_DELAY,
+      pending_config:BankConfigUpdate::default(),
+      pending_config_ready_at:0,
+      deposit_cap:u64::MAX,
+      borrow_cap:u64::MAX,
+      outflow_limit:u64::MAX,
+      outflow_window:0,
+      outflow_window_start:0,
+      outflow_prev_window:0,
+      outflow_cur_window:0,
+      flash_loan_fee:0,
+      flash_loan_amount:0,
+      origination_fee:0,
+      origination_fee_protocol_share:0,
+      protocol_fees:0,
+      swap_program:Pubkey::default(),
+      min_first_deposit:1_000,
+      decimals:6,
+      price_feed_id:[1; 32],
+    }
+  }
+
+  pub(crate) fn empty_user() -> User {
+    User {
+      positions:[Position::default(); MAX_POSITIONS],
+      health_factor:0,
+      owner:Pubkey::new_unique(),
+      delegate:Pubkey::default(),
+      delegate_can_receive:false,
+      index:0,
+    }
+  }
+
+  fn limited_bank(limit:u64, window:i64) -> Bank {
+    Bank { outflow_limit:limit, outflow_window:window, ..test_bank() }
+  }
+
+  #[test]
+  fn guardian_can_pause_but_not_unpause() {
+    let mut bank = test_bank();
+    let guardian = bank.guardian;
+    bank.set_paused(&guardian, true).unwrap();
+    assert!(bank.paused);
+
+    assert!(bank.set_paused(&guardian, false).is_err());
+    assert!(bank.paused);
+    let authority = bank.authority;
+    bank.set_paused(&authority, false).unwrap();
+    assert!(!bank.paused);
+  }
+
+  #[test]
+  fn others_can_not_pause() {
+    let mut bank = test_bank();
+    assert!(bank.set_paused(&Pubkey::new_unique(), true).is_err());
+    assert!(!bank.paused);
+  }
+
+  #[test]
+  fn merge_overwrites_and_remove_drops_the_set_fields() {
+    let mut pending = BankConfigUpdate { max_ltv:Some(7_000), deposit_cap:Some(100), ..Default::default() };
+    pending.merge(&BankConfigUpdate { max_ltv:Some(7_800), borrow_cap:Some(50), ..Default::default() });
+    assert_eq!(pending.max_ltv, Some(7_800));
+    assert_eq!(pending.deposit_cap, Some(100));
+    assert_eq!(pending.borrow_cap, Some(50));
+
+    pending.remove(&BankConfigUpdate { max_ltv:Some(0), deposit_cap:Some(0), ..Default::default() });
+    assert_eq!(pending.max_ltv, None);
+    assert_eq!(pending.deposit_cap, None);
+    assert_eq!(pending.borrow_cap, Some(50));
+    pending.remove(&pending.clone());
+    assert!(pending.is_empty());
+  }
+
+  #[test]
+  fn tightening_applies_right_away() {
+    let mut bank = test_bank();
+    let applied = bank.update_config(&BankConfigUpdate { max_ltv:Some(7_000), ..Default::default() }, NOW).unwrap();
+    assert_eq!(applied.max_ltv, Some(7_000));
+    assert_eq!(bank.max_ltv, 7_000);
+    assert!(bank.pending_config.is_empty());
+    assert_eq!(bank.pending_config_ready_at, 0);
+  }
+
+  #[test]
+  fn loosening_waits_for_the_config_delay() {
+    let mut bank = test_bank();
+    bank.update_config(&BankConfigUpdate { max_ltv:Some(7_800), ..Default::default() }, NOW).unwrap();
+    assert_eq!(bank.max_ltv, 7_500);
+    assert_eq!(bank.pending_config.max_ltv, Some(7_800));
+    assert_eq!(bank.pending_config_ready_at, NOW + MIN_CONFIG_DELAY);
+
+    assert!(bank.apply_pending_config(NOW + MIN_CONFIG_DELAY - 1).is_err());
+    assert_eq!(bank.max_ltv, 7_500);
+    bank.apply_pending_config(NOW + MIN_CONFIG_DELAY).unwrap();
+    assert_eq!(bank.max_ltv, 7_800);
+    assert!(bank.pending_config.is_empty());
+    assert!(bank.apply_pending_config(NOW + MIN_CONFIG_DELAY).is_err());
+  }
+
+  #[test]
+  fn a_tightening_change_drops_the_queued_value() {
+    let mut bank = test_bank();
+    bank.update_config(&BankConfigUpdate { max_ltv:Some(7_800), ..Default::default() }, NOW).unwrap();
+    bank.update_config(&BankConfigUpdate { max_ltv:Some(7_000), ..Default::default() }, NOW + 1).unwrap();
+    assert_eq!(bank.max_ltv, 7_000);
+    assert!(bank.pending_config.is_empty());
+    assert_eq!(bank.pending_config_ready_at, 0);
+  }
+
+  #[test]
+  fn a_queued_change_must_leave_a_valid_config() {
+    let mut bank = test_bank();
+    // max_ltv above the liquidation threshold
+    assert!(bank.update_config(&BankConfigUpdate { max_ltv:Some(8_500), ..Default::default() }, NOW).is_err());
+    assert!(bank.pending_config.is_empty());
+  }
+
+  #[test]
+  fn caps_can_only_be_reduced() {
+    let mut bank = Bank { deposit_cap:1_000, borrow_cap:500, ..test_bank() };
+    bank.pending_config.deposit_cap = Some(2_000);
+    bank.reduce_caps(Some(800), None).unwrap();
+    assert_eq!(bank.deposit_cap, 800);
+    assert_eq!(bank.borrow_cap, 500);
+    assert_eq!(bank.pending_config.deposit_cap, None);
+
+    assert!(bank.reduce_caps(Some(700), Some(600)).is_err());
+    assert_eq!(bank.deposit_cap, 800);
+    bank.reduce_caps(None, Some(500)).unwrap();
+    assert_eq!(bank.borrow_cap, 500);
+  }
+
+  #[test]
+  fn outflow_is_limited_within_a_window() {
+    let mut bank = limited_bank(1_000, 3_600);
+    bank.record_outflow(600, NOW).unwrap();
+    assert!(bank.record_outflow(500, NOW).is_err());
+    assert_eq!(bank.outflow_cur_window, 600);
+
+    // inflows free up room again
+    bank.record_inflow(200, NOW + 10);
+    bank.record_outflow(500, NOW + 10).unwrap();
+    assert_eq!(bank.outflow_cur_window, 900);
+    bank.record_inflow(5_000, NOW + 20);
+    assert_eq!(bank.outflow_cur_window, 0);
+  }
+
+  #[test]
+  fn the_previous_window_fades_out() {
+    let mut bank = limited_bank(1_000, 3_600);
+    bank.record_outflow(900, NOW).unwrap();
+
+    // a new window starts, the previous one still counts in full
+    assert!(bank.record_outflow(200, NOW + 3_600).is_err());
+    bank.record_outflow(100, NOW + 3_600).unwrap();
+    assert_eq!(bank.outflow_prev_window, 900);
+
+    // half way through only half of the previous window counts: 450 + 100
+    assert!(bank.record_outflow(451, NOW + 5_400).is_err());
+    bank.record_outflow(450, NOW + 5_400).unwrap();
+
+    // two windows later everything is forgotten
+    bank.record_outflow(1_000, NOW + 3 * 3_600).unwrap();
+    assert_eq!(bank.outflow_prev_window, 0);
+  }
+
+  #[test]
+  fn a_zero_window_disables_the_limiter() {
+    let mut bank = limited_bank(0, 0);
+    bank.record_outflow(u64::MAX, NOW).unwrap();
+    bank.record_inflow(1, NOW);
+    assert_eq!(bank.outflow_cur_window, 0);
+  }
+
+  #[test]
+  fn changing_the_outflow_window_keeps_the_counted_outflow() {
+    let mut bank = limited_bank(1_000, 3_600);
+    bank.record_outflow(1_000, NOW).unwrap();
+
+    bank.apply_config(&BankConfigUpdate { outflow_window:Some(7_200), ..Default::default() });
+    assert!(bank.record_outflow(1, NOW + 1).is_err());
+  }
+
+  #[test]
+  fn every_bank_gets_its_own_position() {
+    let mut user = empty_user();
+    let mints:Vec<Pubkey> = (0..MAX_POSITIONS).map(|_| Pubkey::new_unique()).collect();
+    for (amount, mint) in mints.iter().enumerate() {
+      *user.deposit_position(mint).unwrap().1 = amount as u64 + 1;
+    }
+    *user.borrow_position(&mints[0]).unwrap().1 = 10;
+
+    assert_eq!(user.open_positions().count(), MAX_POSITIONS);
+    assert_eq!(user.deposit_shares(&mints[2]), 3);
+    assert_eq!(user.borrow_shares(&mints[0]), 10);
+    assert!(user.deposit_position(&Pubkey::new_unique()).is_err());
+  }
+
+  #[test]
+  fn an_emptied_position_frees_its_slot() {
+    let mut user = empty_user();
+    let (mint, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    *user.deposit_position(&mint).unwrap().1 = 5;
+    user.release_position(&mint);
+    assert!(user.position(&mint).is_some());
+
+    *user.deposit_position(&mint).unwrap().1 = 0;
+    user.release_position(&mint);
+    assert!(user.position(&mint).is_none());
+    *user.deposit_position(&other).unwrap().1 = 1;
+    assert_eq!(user.positions[0].mint, other);
+  }
+}
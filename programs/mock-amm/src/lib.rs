@@ -0,0 +1,185 @@
+// Same as lending-app: the IDL resize handler generated by `#[program]` uses the deprecated
+// `AccountInfo::realloc`.
+#![allow(deprecated)]
+
+//! Swap program for tests only. It trades one mint for another at a fixed rate out of
+//! pre-funded vaults, and takes `amount_in` and `min_amount_out` as its last two arguments
+//! so it can be plugged into a bank as `swap_program` with `swap_data` set to the `swap`
+//! discriminator.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("5TbuoDsPigy4gbMXsXsacFyfxz6B6hPuNirp9EgY6e5t");
+
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    pub fn init_pool(ctx:Context<InitPool>, rate_numerator:u64, rate_denominator:u64) -> Result<()> {
+        if rate_numerator == 0 || rate_denominator == 0 {
+            return Err(MockAmmError::InvalidRate.into());
+        }
+        ctx.accounts.pool.set_inner(Pool {
+            input_mint:ctx.accounts.input_mint.key(),
+            output_mint:ctx.accounts.output_mint.key(),
+            rate_numerator,
+            rate_denominator,
+            bump:ctx.bumps.pool,
+        });
+        Ok(())
+    }
+
+    pub fn swap(ctx:Context<Swap>, amount_in:u64, min_amount_out:u64) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let amount_out = pool.quote(amount_in)?;
+        if amount_out < min_amount_out {
+            return Err(MockAmmError::SlippageExceeded.into());
+        }
+
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from:ctx.accounts.source.to_account_info(),
+                    to:ctx.accounts.input_vault.to_account_info(),
+                    authority:ctx.accounts.signer.to_account_info(),
+                    mint:ctx.accounts.input_mint.to_account_info(),
+                },
+            ),
+            amount_in,
+            ctx.accounts.input_mint.decimals,
+        )?;
+
+        let signer_seeds:&[&[&[u8]]] = &[&[
+            b"pool",
+            pool.input_mint.as_ref(),
+            pool.output_mint.as_ref(),
+            &[pool.bump],
+        ]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from:ctx.accounts.output_vault.to_account_info(),
+                    to:ctx.accounts.destination.to_account_info(),
+                    authority:ctx.accounts.pool.to_account_info(),
+                    mint:ctx.accounts.output_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_out,
+            ctx.accounts.output_mint.decimals,
+        )?;
+
+        msg!("Swapped {} for {}", amount_in, amount_out);
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub input_mint:Pubkey,
+    pub output_mint:Pubkey,
+    // output base units paid per input base unit, as a fraction
+    pub rate_numerator:u64,
+    pub rate_denominator:u64,
+    pub bump:u8,
+}
+
+impl Pool {
+    pub fn quote(&self, amount_in:u64) -> Result<u64> {
+        let amount_out = (amount_in as u128)
+            .checked_mul(self.rate_numerator as u128)
+            .ok_or(MockAmmError::MathOverflow)?
+            / self.rate_denominator as u128;
+        Ok(u64::try_from(amount_out).map_err(|_| MockAmmError::MathOverflow)?)
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitPool<'info> {
+    #[account(mut)]
+    pub signer:Signer<'info>,
+    #[account(
+        init,
+        payer=signer,
+        space=8 + Pool::INIT_SPACE,
+        seeds=[b"pool",input_mint.key().as_ref(),output_mint.key().as_ref()],
+        bump
+    )]
+    pub pool:Account<'info,Pool>,
+    #[account(
+        init,
+        payer=signer,
+        token::mint=input_mint,
+        token::authority=pool,
+        token::token_program=token_program,
+        seeds=[b"vault",pool.key().as_ref(),input_mint.key().as_ref()],
+        bump
+    )]
+    pub input_vault:InterfaceAccount<'info,TokenAccount>,
+    // funded by minting straight into it
+    #[account(
+        init,
+        payer=signer,
+        token::mint=output_mint,
+        token::authority=pool,
+        token::token_program=token_program,
+        seeds=[b"vault",pool.key().as_ref(),output_mint.key().as_ref()],
+        bump
+    )]
+    pub output_vault:InterfaceAccount<'info,TokenAccount>,
+    pub input_mint:InterfaceAccount<'info,Mint>,
+    pub output_mint:InterfaceAccount<'info,Mint>,
+    pub system_program:Program<'info,System>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub signer:Signer<'info>,
+    #[account(
+        seeds=[b"pool",input_mint.key().as_ref(),output_mint.key().as_ref()],
+        bump=pool.bump
+    )]
+    pub pool:Account<'info,Pool>,
+    #[account(
+        mut,
+        token::mint=input_mint,
+        token::token_program=token_program,
+    )]
+    pub source:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        token::mint=output_mint,
+        token::token_program=token_program,
+    )]
+    pub destination:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        seeds=[b"vault",pool.key().as_ref(),input_mint.key().as_ref()],
+        bump
+    )]
+    pub input_vault:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        seeds=[b"vault",pool.key().as_ref(),output_mint.key().as_ref()],
+        bump
+    )]
+    pub output_vault:InterfaceAccount<'info,TokenAccount>,
+    pub input_mint:InterfaceAccount<'info,Mint>,
+    pub output_mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+#[error_code]
+pub enum MockAmmError {
+    #[msg("Rate numerator and denominator must be non-zero")]
+    InvalidRate,
+    #[msg("Swap output is below the minimum")]
+    SlippageExceeded,
+    #[msg("Math overflow")]
+    MathOverflow,
+}
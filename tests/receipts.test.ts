@@ -0,0 +1,153 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import assert from "assert";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, PublicKey, Transaction } from "@solana/web3.js";
+import { createMint, mintTo } from "spl-token-bankrun";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createTransferCheckedInstruction,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+  unpackAccount,
+} from "@solana/spl-token";
+import { it } from "mocha";
+import { startAnchor } from "solana-bankrun";
+import type { LendingApp } from "../target/types/lending_app";
+import LendingIDL from "../target/idl/lending_app.json";
+
+// Receipts minted by `supply` are plain tokens: whoever holds them redeems the shares.
+// Needs `anchor build` first, the program is loaded from target/deploy.
+describe("Receipt token tests", async () => {
+  const context = await startAnchor("", [], []);
+  const provider = new BankrunProvider(context);
+  const banksClient = context.banksClient;
+  const payer = provider.wallet.payer;
+  const strategy = Keypair.generate();
+
+  const program: Program<LendingApp> = new Program(LendingIDL as LendingApp, provider);
+
+  const USDC_FEED_ID = Array.from(
+    Buffer.from("eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a", "hex")
+  );
+
+  const mintUsdc = await createMint(banksClient, payer, payer.publicKey, null, 6, undefined, TOKEN_2022_PROGRAM_ID);
+
+  const pda = (seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const ata = (mint: PublicKey, owner = payer.publicKey) =>
+    getAssociatedTokenAddressSync(mint, owner, false, TOKEN_2022_PROGRAM_ID);
+
+  const usdcBank = pda([Buffer.from("bank"), mintUsdc.toBuffer()]);
+  const receiptMint = pda([Buffer.from("receipt"), mintUsdc.toBuffer()]);
+  const userAccount = pda([Buffer.from("user"), payer.publicKey.toBuffer(), Buffer.from([0])]);
+
+  const balance = async (address: PublicKey) =>
+    unpackAccount(address, await banksClient.getAccount(address), TOKEN_2022_PROGRAM_ID).amount;
+
+  const createAtas = async (accounts: [PublicKey, PublicKey][]) => {
+    const tx = new Transaction().add(
+      ...accounts.map(([mint, owner]) =>
+        createAssociatedTokenAccountIdempotentInstruction(
+          payer.publicKey,
+          ata(mint, owner),
+          owner,
+          mint,
+          TOKEN_2022_PROGRAM_ID,
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        )
+      )
+    );
+    await provider.sendAndConfirm(tx);
+  };
+
+  const redeem = (holder: Keypair, shares: BN) =>
+    program.methods
+      .redeemReceipts(shares)
+      .accountsPartial({
+        signer: holder.publicKey,
+        mint: mintUsdc,
+        signerReceiptAccount: ata(receiptMint, holder.publicKey),
+        recipientTokenAccount: ata(mintUsdc, holder.publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([holder])
+      .rpc();
+
+  it("Supplies USDC for receipts", async () => {
+    await program.methods
+      .initBank(new BN(7500), new BN(8500), new BN(500), new BN(5000), new BN(500), new BN(3600), USDC_FEED_ID)
+      .accounts({ signer: payer.publicKey, tokenMintAddress: mintUsdc, tokenProgram: TOKEN_2022_PROGRAM_ID })
+      .rpc();
+    await createAtas([
+      [mintUsdc, payer.publicKey],
+      [receiptMint, payer.publicKey],
+      [mintUsdc, strategy.publicKey],
+      [receiptMint, strategy.publicKey],
+    ]);
+    await mintTo(banksClient, payer, mintUsdc, ata(mintUsdc), payer, 1_000 * 10 ** 6, [], TOKEN_2022_PROGRAM_ID);
+
+    await program.methods
+      .supply(new BN(100 * 10 ** 6))
+      .accountsPartial({
+        signer: payer.publicKey,
+        mint: mintUsdc,
+        signerTokenAccount: ata(mintUsdc),
+        receiptAccount: ata(receiptMint),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const bank = await program.account.bank.fetch(usdcBank);
+    assert.equal((await balance(ata(receiptMint))).toString(), bank.totalDepositShares.toString());
+  });
+
+  it("Lets the holder of transferred receipts redeem them", async () => {
+    const shares = (await balance(ata(receiptMint))) / BigInt(2);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        createTransferCheckedInstruction(
+          ata(receiptMint),
+          receiptMint,
+          ata(receiptMint, strategy.publicKey),
+          payer.publicKey,
+          shares,
+          6,
+          [],
+          TOKEN_2022_PROGRAM_ID
+        )
+      )
+    );
+
+    await redeem(strategy, new BN(shares.toString()));
+
+    assert.equal(await balance(ata(receiptMint, strategy.publicKey)), BigInt(0));
+    assert.ok((await balance(ata(mintUsdc, strategy.publicKey))) > BigInt(0));
+  });
+
+  it("Rejects redeeming more receipts than held", async () => {
+    const held = await balance(ata(receiptMint));
+    await assert.rejects(redeem(payer, new BN((held + BigInt(1)).toString())));
+  });
+
+  it("Keeps position deposits out of the receipt supply", async () => {
+    const before = await balance(ata(receiptMint));
+    await program.methods
+      .initUser(0)
+      .accountsPartial({ signer: payer.publicKey, userAccount })
+      .rpc();
+    await program.methods
+      .deposit(new BN(10 * 10 ** 6))
+      .accountsPartial({
+        signer: payer.publicKey,
+        beneficiary: payer.publicKey,
+        userLendingProgramAcc: userAccount,
+        tokenMintAddress: mintUsdc,
+        tokenProgram2022: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const user = await program.account.user.fetch(userAccount);
+    assert.ok(user.positions.find((position) => position.mint.equals(mintUsdc)).depositedShares.gtn(0));
+    assert.equal(await balance(ata(receiptMint)), before);
+  });
+});
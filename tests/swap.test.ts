@@ -0,0 +1,446 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import assert from "assert";
+import { BankrunProvider } from "anchor-bankrun";
+import { PublicKey } from "@solana/web3.js";
+import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
+import { createMint, mintTo } from "spl-token-bankrun";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { Transaction } from "@solana/web3.js";
+import { it } from "mocha";
+import { Clock, startAnchor } from "solana-bankrun";
+import type { LendingApp } from "../target/types/lending_app";
+import type { MockAmm } from "../target/types/mock_amm";
+import LendingIDL from "../target/idl/lending_app.json";
+import MockAmmIDL from "../target/idl/mock_amm.json";
+
+// Swaps collateral through the bank's swap adapter, with the mock AMM as swap program.
+// Needs `anchor build` first, both programs are loaded from target/deploy.
+describe("Swap adapter tests", async () => {
+  const context = await startAnchor("", [], []);
+  const provider = new BankrunProvider(context);
+  const banksClient = context.banksClient;
+  const payer = provider.wallet.payer;
+
+  const program: Program<LendingApp> = new Program(LendingIDL as LendingApp, provider);
+  const amm: Program<MockAmm> = new Program(MockAmmIDL as MockAmm, provider);
+  const pythSolanaReceiver = new PythSolanaReceiver({
+    connection: provider.connection,
+    wallet: provider.wallet,
+  });
+
+  const SOL_FEED_ID =
+    "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+  const USDC_FEED_ID =
+    "eaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a";
+  const solPriceUpdate = PublicKey.unique();
+  const usdcPriceUpdate = PublicKey.unique();
+
+  // 1 USDC buys 1/150 SOL
+  const RATE_NUMERATOR = new BN(20);
+  const RATE_DENOMINATOR = new BN(3);
+  // and 1 SOL buys 150 USDC in the reverse pool
+  const REVERSE_RATE_NUMERATOR = new BN(3);
+  const REVERSE_RATE_DENOMINATOR = new BN(20);
+  const CONFIG_DELAY = 3600;
+
+  const mintUsdc = await createMint(banksClient, payer, payer.publicKey, null, 6, undefined, TOKEN_2022_PROGRAM_ID);
+  const mintSol = await createMint(banksClient, payer, payer.publicKey, null, 9, undefined, TOKEN_2022_PROGRAM_ID);
+
+  const pda = (seeds: Buffer[], programId = program.programId) =>
+    PublicKey.findProgramAddressSync(seeds, programId)[0];
+  const ata = (mint: PublicKey) =>
+    getAssociatedTokenAddressSync(mint, payer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  const usdcBank = pda([Buffer.from("bank"), mintUsdc.toBuffer()]);
+  const solBank = pda([Buffer.from("bank"), mintSol.toBuffer()]);
+  const userAccount = pda([Buffer.from("user"), payer.publicKey.toBuffer(), Buffer.from([0])]);
+  // second sub-account of the payer, only supplies USDC liquidity
+  const lpAccount = pda([Buffer.from("user"), payer.publicKey.toBuffer(), Buffer.from([1])]);
+  const poolFor = (input: PublicKey, output: PublicKey) =>
+    pda([Buffer.from("pool"), input.toBuffer(), output.toBuffer()], amm.programId);
+  const vaultFor = (pool: PublicKey, mint: PublicKey) =>
+    pda([Buffer.from("vault"), pool.toBuffer(), mint.toBuffer()], amm.programId);
+  const pool = poolFor(mintUsdc, mintSol);
+  const outputVault = vaultFor(pool, mintSol);
+  const reversePool = poolFor(mintSol, mintUsdc);
+
+  const swapData = Buffer.from(MockAmmIDL.instructions.find((ix) => ix.name === "swap").discriminator);
+
+  type UserAccount = Awaited<ReturnType<typeof program.account.user.fetch>>;
+  const position = (user: UserAccount, mint: PublicKey) =>
+    user.positions.find((slot) => slot.mint.equals(mint));
+  const depositShares = (user: UserAccount, mint: PublicKey) => position(user, mint)?.depositedShares ?? new BN(0);
+  const borrowShares = (user: UserAccount, mint: PublicKey) => position(user, mint)?.borrowedShares ?? new BN(0);
+
+  const now = async () => (await banksClient.getClock()).unixTimestamp;
+
+  const warp = async (seconds: bigint) => {
+    const clock = await banksClient.getClock();
+    context.setClock(
+      new Clock(
+        clock.slot,
+        clock.epochStartTimestamp,
+        clock.epoch,
+        clock.leaderScheduleEpoch,
+        clock.unixTimestamp + seconds
+      )
+    );
+  };
+
+  // Writes a verified price update published at the current clock.
+  const setPrice = async (address: PublicKey, feedId: string, price: number, exponent: number) => {
+    const publishTime = new BN((await now()).toString());
+    const data = await pythSolanaReceiver.receiver.coder.accounts.encode("priceUpdateV2", {
+      writeAuthority: payer.publicKey,
+      verificationLevel: { full: {} },
+      priceMessage: {
+        feedId: Array.from(Buffer.from(feedId, "hex")),
+        price: new BN(price),
+        conf: new BN(0),
+        exponent,
+        publishTime,
+        prevPublishTime: publishTime,
+        emaPrice: new BN(price),
+        emaConf: new BN(0),
+      },
+      postedSlot: new BN(0),
+    });
+    context.setAccount(address, {
+      lamports: 1_000_000_000,
+      data,
+      owner: pythSolanaReceiver.receiver.programId,
+      executable: false,
+    });
+  };
+
+  const createAtas = async (mints: PublicKey[]) => {
+    const tx = new Transaction().add(
+      ...mints.map((mint) =>
+        createAssociatedTokenAccountIdempotentInstruction(
+          payer.publicKey,
+          ata(mint),
+          payer.publicKey,
+          mint,
+          TOKEN_2022_PROGRAM_ID,
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        )
+      )
+    );
+    await provider.sendAndConfirm(tx);
+  };
+
+  // Accounts of the mock AMM swap from `input` to `output`, passed after the health accounts.
+  const adapterAccounts = (input: PublicKey, output: PublicKey) => {
+    const pool = poolFor(input, output);
+    return [
+      { pubkey: payer.publicKey, isSigner: true, isWritable: false },
+      { pubkey: pool, isSigner: false, isWritable: false },
+      { pubkey: ata(input), isSigner: false, isWritable: true },
+      { pubkey: ata(output), isSigner: false, isWritable: true },
+      { pubkey: vaultFor(pool, input), isSigner: false, isWritable: true },
+      { pubkey: vaultFor(pool, output), isSigner: false, isWritable: true },
+      { pubkey: input, isSigner: false, isWritable: false },
+      { pubkey: output, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
+    ];
+  };
+
+  // A new swap program is queued behind the config delay
+  const setSwapProgram = async (mint: PublicKey) => {
+    await program.methods
+      .updateBankConfig({
+        maxLtv: null,
+        liquidationThreshold: null,
+        liquidationBonus: null,
+        closeFactor: null,
+        interestRate: null,
+        configDelay: null,
+        depositCap: null,
+        borrowCap: null,
+        outflowLimit: null,
+        outflowWindow: null,
+        flashLoanFee: null,
+        originationFee: null,
+        originationFeeProtocolShare: null,
+        swapProgram: amm.programId,
+        priceFeedId: null,
+      })
+      .accounts({ signer: payer.publicKey, mint })
+      .rpc();
+    await warp(BigInt(CONFIG_DELAY));
+    await program.methods.applyBankConfig().accounts({ signer: payer.publicKey, mint }).rpc();
+  };
+
+  const setPrices = async (solPrice: number) => {
+    await setPrice(usdcPriceUpdate, USDC_FEED_ID, 100_000_000, -8);
+    await setPrice(solPriceUpdate, SOL_FEED_ID, solPrice * 100_000_000, -8);
+  };
+
+  const swapCollateral = (amount: BN, minAmountOut: BN) =>
+    program.methods
+      .swapCollateral(amount, minAmountOut, swapData)
+      .accountsPartial({
+        signer: payer.publicKey,
+        userAccount,
+        fromMint: mintUsdc,
+        toMint: mintSol,
+        signerFromAccount: ata(mintUsdc),
+        signerToAccount: ata(mintSol),
+        fromPriceUpdate: usdcPriceUpdate,
+        toPriceUpdate: solPriceUpdate,
+        swapProgram: amm.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(adapterAccounts(mintUsdc, mintSol))
+      .rpc();
+
+  // Borrows USDC, swaps it to SOL and deposits the SOL, `iterations` times.
+  const loopLeverage = (borrowAmount: BN, minAmountOut: BN, iterations: number) =>
+    program.methods
+      .loopLeverage(borrowAmount, minAmountOut, iterations, new BN(0), swapData)
+      .accountsPartial({
+        signer: payer.publicKey,
+        userAccount,
+        debtMint: mintUsdc,
+        collateralMint: mintSol,
+        signerDebtAccount: ata(mintUsdc),
+        signerCollateralAccount: ata(mintSol),
+        collateralPriceUpdate: solPriceUpdate,
+        debtPriceUpdate: usdcPriceUpdate,
+        swapProgram: amm.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(adapterAccounts(mintUsdc, mintSol))
+      .rpc();
+
+  // Borrows SOL and swaps it to USDC to repay the USDC debt.
+  const swapDebt = (borrowAmount: BN, minRepayAmount: BN) =>
+    program.methods
+      .swapDebt(borrowAmount, minRepayAmount, swapData)
+      .accountsPartial({
+        signer: payer.publicKey,
+        userAccount,
+        newDebtMint: mintSol,
+        oldDebtMint: mintUsdc,
+        signerNewDebtAccount: ata(mintSol),
+        signerOldDebtAccount: ata(mintUsdc),
+        newDebtPriceUpdate: solPriceUpdate,
+        oldDebtPriceUpdate: usdcPriceUpdate,
+        swapProgram: amm.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(adapterAccounts(mintSol, mintUsdc))
+      .rpc();
+
+  // Withdraws SOL collateral and swaps it to USDC to repay the USDC debt.
+  const repayWithCollateral = (collateralAmount: BN, minRepayAmount: BN) =>
+    program.methods
+      .repayWithCollateral(collateralAmount, minRepayAmount, swapData)
+      .accountsPartial({
+        signer: payer.publicKey,
+        userAccount,
+        collateralMint: mintSol,
+        debtMint: mintUsdc,
+        signerCollateralAccount: ata(mintSol),
+        signerDebtAccount: ata(mintUsdc),
+        collateralPriceUpdate: solPriceUpdate,
+        debtPriceUpdate: usdcPriceUpdate,
+        swapProgram: amm.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(adapterAccounts(mintSol, mintUsdc))
+      .rpc();
+
+  it("Sets up banks, the pool and a USDC deposit", async () => {
+    for (const mint of [mintUsdc, mintSol]) {
+      await program.methods
+        .initBank(
+          new BN(7500),
+          new BN(8500),
+          new BN(500),
+          new BN(5000),
+          new BN(500),
+          new BN(CONFIG_DELAY),
+          Array.from(Buffer.from(mint.equals(mintUsdc) ? USDC_FEED_ID : SOL_FEED_ID, "hex"))
+        )
+        .accounts({ signer: payer.publicKey, tokenMintAddress: mint, tokenProgram: TOKEN_2022_PROGRAM_ID })
+        .rpc();
+    }
+
+    await amm.methods
+      .initPool(RATE_NUMERATOR, RATE_DENOMINATOR)
+      .accounts({
+        signer: payer.publicKey,
+        inputMint: mintUsdc,
+        outputMint: mintSol,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+    await mintTo(banksClient, payer, mintSol, outputVault, payer, 1_000 * 10 ** 9, [], TOKEN_2022_PROGRAM_ID);
+
+    await setSwapProgram(mintUsdc);
+    const bank = await program.account.bank.fetch(usdcBank);
+    assert.ok(bank.swapProgram.equals(amm.programId));
+
+    await program.methods
+      .initUser(0)
+      .accountsPartial({ signer: payer.publicKey, userAccount })
+      .rpc();
+    await createAtas([mintUsdc, mintSol]);
+    await mintTo(banksClient, payer, mintUsdc, ata(mintUsdc), payer, 1_000 * 10 ** 6, [], TOKEN_2022_PROGRAM_ID);
+
+    await program.methods
+      .deposit(new BN(100 * 10 ** 6))
+      .accountsPartial({
+        signer: payer.publicKey,
+        beneficiary: payer.publicKey,
+        userLendingProgramAcc: userAccount,
+        tokenMintAddress: mintUsdc,
+        tokenProgram2022: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+  });
+
+  it("Rejects a swap below the minimum output", async () => {
+    await setPrices(150);
+
+    const amount = new BN(50 * 10 ** 6);
+    const expected = amount.mul(RATE_NUMERATOR).div(RATE_DENOMINATOR);
+    await assert.rejects(swapCollateral(amount, expected.addn(1)));
+  });
+
+  it("Moves collateral from USDC to SOL through the mock AMM", async () => {
+    const amount = new BN(50 * 10 ** 6);
+    const expected = amount.mul(RATE_NUMERATOR).div(RATE_DENOMINATOR);
+    const before = await program.account.user.fetch(userAccount);
+
+    await swapCollateral(amount, expected);
+
+    const user = await program.account.user.fetch(userAccount);
+    const solBankAccount = await program.account.bank.fetch(solBank);
+    assert.ok(depositShares(user, mintUsdc).lt(depositShares(before, mintUsdc)));
+    assert.ok(depositShares(user, mintSol).gtn(0));
+    assert.ok(solBankAccount.totalDeposits.eq(expected));
+  });
+
+  it("Adds USDC liquidity and moves the rest of the collateral to SOL", async () => {
+    await program.methods
+      .initUser(1)
+      .accountsPartial({ signer: payer.publicKey, userAccount: lpAccount })
+      .rpc();
+    await program.methods
+      .deposit(new BN(500 * 10 ** 6))
+      .accountsPartial({
+        signer: payer.publicKey,
+        beneficiary: payer.publicKey,
+        userLendingProgramAcc: lpAccount,
+        tokenMintAddress: mintUsdc,
+        tokenProgram2022: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    // leaves about 1 USDC, the position is backed by SOL from here on
+    const amount = new BN(49 * 10 ** 6);
+    await swapCollateral(amount, amount.mul(RATE_NUMERATOR).div(RATE_DENOMINATOR));
+  });
+
+  describe("loop_leverage", () => {
+    const borrowAmount = new BN(10 * 10 ** 6);
+    const expected = borrowAmount.mul(RATE_NUMERATOR).div(RATE_DENOMINATOR);
+
+    it("Rejects a loop below the minimum output", async () => {
+      await assert.rejects(loopLeverage(borrowAmount, expected.addn(1), 2));
+    });
+
+    it("Rejects a loop that leaves the position unhealthy", async () => {
+      // the pool still pays 1/150 SOL per USDC, but the oracle values the SOL at $15
+      await setPrices(15);
+      await assert.rejects(loopLeverage(borrowAmount, expected, 2));
+      await setPrices(150);
+    });
+
+    it("Loops USDC debt into SOL collateral", async () => {
+      const before = await program.account.user.fetch(userAccount);
+      const solBankBefore = await program.account.bank.fetch(solBank);
+
+      await loopLeverage(borrowAmount, expected, 2);
+
+      const user = await program.account.user.fetch(userAccount);
+      const solBankAccount = await program.account.bank.fetch(solBank);
+      assert.ok(borrowShares(user, mintUsdc).eq(borrowAmount.muln(2)));
+      assert.ok(depositShares(user, mintSol).gt(depositShares(before, mintSol)));
+      assert.ok(solBankAccount.totalDeposits.eq(solBankBefore.totalDeposits.add(expected.muln(2))));
+    });
+  });
+
+  describe("swap_debt", () => {
+    const borrowAmount = new BN(5 * 10 ** 7);
+    const expected = borrowAmount.mul(REVERSE_RATE_NUMERATOR).div(REVERSE_RATE_DENOMINATOR);
+
+    it("Opens a SOL to USDC pool and allows swaps on the SOL bank", async () => {
+      await amm.methods
+        .initPool(REVERSE_RATE_NUMERATOR, REVERSE_RATE_DENOMINATOR)
+        .accounts({
+          signer: payer.publicKey,
+          inputMint: mintSol,
+          outputMint: mintUsdc,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      await mintTo(banksClient, payer, mintUsdc, vaultFor(reversePool, mintUsdc), payer, 1_000 * 10 ** 6, [], TOKEN_2022_PROGRAM_ID);
+      await setSwapProgram(mintSol);
+      await setPrices(150);
+    });
+
+    it("Rejects a debt swap below the minimum output", async () => {
+      await assert.rejects(swapDebt(borrowAmount, expected.addn(1)));
+    });
+
+    it("Rejects a debt swap that leaves the position unhealthy", async () => {
+      // 0.7 SOL of new debt is worth more than 75% of the collateral
+      const tooMuch = new BN(7 * 10 ** 8);
+      await assert.rejects(swapDebt(tooMuch, tooMuch.mul(REVERSE_RATE_NUMERATOR).div(REVERSE_RATE_DENOMINATOR)));
+    });
+
+    it("Moves part of the USDC debt to SOL", async () => {
+      const before = await program.account.user.fetch(userAccount);
+
+      await swapDebt(borrowAmount, expected);
+
+      const user = await program.account.user.fetch(userAccount);
+      assert.ok(borrowShares(user, mintSol).eq(borrowAmount));
+      assert.ok(borrowShares(user, mintUsdc).lt(borrowShares(before, mintUsdc)));
+    });
+  });
+
+  describe("repay_with_collateral", () => {
+    const collateralAmount = new BN(5 * 10 ** 7);
+    const expected = collateralAmount.mul(REVERSE_RATE_NUMERATOR).div(REVERSE_RATE_DENOMINATOR);
+
+    it("Rejects a repay below the minimum output", async () => {
+      await assert.rejects(repayWithCollateral(collateralAmount, expected.addn(1)));
+    });
+
+    it("Rejects a repay that leaves the position unhealthy", async () => {
+      // at $1 per SOL the position stays under water after the repay
+      await setPrices(1);
+      await assert.rejects(repayWithCollateral(collateralAmount, expected));
+      await setPrices(150);
+    });
+
+    it("Repays USDC debt with SOL collateral", async () => {
+      const before = await program.account.user.fetch(userAccount);
+
+      await repayWithCollateral(collateralAmount, expected);
+
+      const user = await program.account.user.fetch(userAccount);
+      assert.ok(borrowShares(user, mintUsdc).lt(borrowShares(before, mintUsdc)));
+      assert.ok(depositShares(user, mintSol).lt(depositShares(before, mintSol)));
+    });
+  });
+});
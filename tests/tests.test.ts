@@ -7,7 +7,11 @@ import type { LendingApp } from "../programs/lending-app/src/build/lending_app";
 import { BankrunContextWrapper } from "./fixtures/bankrunContextWrapper";
 import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
 import { createMint, mintTo } from "spl-token-bankrun";
-import { createAccount, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import {
+  createAccount,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
 import { it } from "mocha";
 import { startAnchor } from "solana-bankrun";
 
@@ -99,6 +103,22 @@ describe("Lending tests", async () => {
     [Buffer.from("bank"), mintSol.toBuffer()],
     program.programId
   );
+  // the payer's first position
+  const [userAccount] = PublicKey.findProgramAddressSync(
+    [Buffer.from("user"), payer.publicKey.toBuffer(), Buffer.from([0])],
+    program.programId
+  );
+  const healthAccounts = (bank: PublicKey, priceUpdate: string) => [
+    { pubkey: bank, isSigner: false, isWritable: false },
+    { pubkey: new PublicKey(priceUpdate), isSigner: false, isWritable: false },
+  ];
+  const tokenAccount = (mint: PublicKey) =>
+    getAssociatedTokenAddressSync(
+      mint,
+      payer.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
 
   const amount = 10_000 * 10 ** 9;
 
@@ -127,11 +147,12 @@ describe("Lending tests", async () => {
     program.methods
       .initBank(
         new BN(7500), // max LTV
-        new PublicKey(solBankAccount),
         new BN(8500), // max Liquidation Threshold         //85%
         new BN(500), //5%
         new BN(5000), //50%
-        new BN(500) //5%
+        new BN(500), //5%
+        new BN(3600), // config delay, 1 hour
+        Array.from(Buffer.from(SOL_FEED_ID, "hex"))
       )
       .accounts({
         signer: payer.publicKey,
@@ -157,11 +178,12 @@ describe("Lending tests", async () => {
     program.methods
       .initBank(
         new BN(7500), // max LTV
-        new PublicKey(usdcBankAccount),
         new BN(8500), // max Liquidation Threshold         //85%
         new BN(500), //5%
         new BN(5000), //50%
-        new BN(500) //5%
+        new BN(500), //5%
+        new BN(3600), // config delay, 1 hour
+        Array.from(Buffer.from(USDC_FEED_ID, "hex"))
       )
       .accounts({
         signer: payer.publicKey,
@@ -186,10 +208,9 @@ describe("Lending tests", async () => {
 
   it("Creating User Account", async () => {
     const user_account = await program.methods
-      .initUser()
-      .accounts({
-        mintAddress: mintUsdc,
-        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      .initUser(0)
+      .accountsPartial({
+        userAccount,
         signer: payer.publicKey,
       })
       .rpc();
@@ -202,8 +223,10 @@ describe("Lending tests", async () => {
       .deposit(new BN(1000000000))
       .accountsPartial({
         tokenMintAddress: mintUsdc,
-        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenProgram2022: TOKEN_2022_PROGRAM_ID,
         signer: payer.publicKey,
+        beneficiary: payer.publicKey,
+        userLendingProgramAcc: userAccount,
       })
       .rpc({ commitment: "confirmed" });
     console.log(`DEPOSIT OF USDC`, deposit);
@@ -214,8 +237,10 @@ describe("Lending tests", async () => {
       .deposit(new BN(1000000000))
       .accountsPartial({
         tokenMintAddress: mintSol,
-        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenProgram2022: TOKEN_2022_PROGRAM_ID,
         signer: payer.publicKey,
+        beneficiary: payer.publicKey,
+        userLendingProgramAcc: userAccount,
       })
       .rpc({ commitment: "confirmed" });
     console.log(`DEPOSIT OF SOL`, deposit);
@@ -224,12 +249,16 @@ describe("Lending tests", async () => {
   it("Testing Borrow Instruction for Usdc", async () => {
     const borrowUsdc = await program.methods
       .borrow(new BN(500000000))
-      .accounts({
+      .accountsPartial({
         mint: mintUsdc,
         priceUpdate: usdcUsdPriceFeedAccount,
+        recipientTokenAccount: tokenAccount(mintUsdc),
+        userAccount,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
         signer: payer.publicKey,
       })
+      // the position's other bank is priced for the health check
+      .remainingAccounts(healthAccounts(solBankAccount, solUsdPriceFeedAccount))
       .rpc();
     console.log(borrowUsdc);
   });
@@ -237,12 +266,16 @@ describe("Lending tests", async () => {
   it("Testing Borrow Instruction for Sol", async () => {
     const borrowSol = await program.methods
       .borrow(new BN(500000000))
-      .accounts({
+      .accountsPartial({
         mint: mintSol,
         priceUpdate: solUsdPriceFeedAccount,
+        recipientTokenAccount: tokenAccount(mintSol),
+        userAccount,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
         signer: payer.publicKey,
       })
+      // the position's other bank is priced for the health check
+      .remainingAccounts(healthAccounts(usdcBankAccount, usdcUsdPriceFeedAccount))
       .rpc();
     console.log(borrowSol);
   });